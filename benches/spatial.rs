@@ -0,0 +1,228 @@
+//! Cost of a fixed tick as the number of snakes grows: moving the bodies,
+//! keeping the spatial indexes up to date, then looking for the snakes and
+//! obstacles hit by every head. Compared with the scan of every segment and
+//! obstacle the indexes replace.
+//!
+//! Run with `cargo bench --bench spatial`.
+
+#![feature(test)]
+
+extern crate test;
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use snake::{
+    collision::{distance_to_segment, point_in_polygon},
+    components::{obstacle::Obstacle, snake::Snake},
+    config::SnakeConfig,
+    levels::arena::{Arena, BoundaryMode},
+    spatial::SpatialIndex,
+};
+use test::{black_box, Bencher};
+
+/// Points of every body, about a grown snake
+const BODY_POINTS: usize = 100;
+
+const OBSTACLES: usize = 24;
+
+/// Snake going round a circle, so that it keeps moving inside the arena
+struct Lap {
+    center: Vec2,
+    radius: f32,
+    angle: f32,
+}
+
+struct Scene {
+    config: SnakeConfig,
+    laps: Vec<Lap>,
+    bodies: Vec<Vec<Vec2>>,
+    obstacles: Vec<Vec<Vec2>>,
+    snake_index: SpatialIndex<Snake>,
+    obstacle_index: SpatialIndex<Obstacle>,
+}
+
+fn entity(index: usize) -> Entity {
+    Entity::from_raw(index as u32)
+}
+
+impl Scene {
+    fn new(snakes: usize) -> Self {
+        let arena = Arena {
+            min: Vec2::new(-640.0, -360.0),
+            max: Vec2::new(640.0, 360.0),
+            boundary: BoundaryMode::Solid,
+        };
+        let mut rng = StdRng::seed_from_u64(0);
+
+        let laps = (0..snakes)
+            .map(|_| {
+                let radius = rng.gen_range(40.0..150.0);
+                Lap {
+                    center: Vec2::new(
+                        rng.gen_range(arena.min.x + radius..arena.max.x - radius),
+                        rng.gen_range(arena.min.y + radius..arena.max.y - radius),
+                    ),
+                    radius,
+                    angle: rng.gen_range(0.0..std::f32::consts::TAU),
+                }
+            })
+            .collect();
+        let obstacles: Vec<Vec<Vec2>> = (0..OBSTACLES)
+            .map(|_| {
+                let min = Vec2::new(
+                    rng.gen_range(arena.min.x..arena.max.x - 60.0),
+                    rng.gen_range(arena.min.y..arena.max.y - 60.0),
+                );
+                let size = Vec2::new(rng.gen_range(10.0..60.0), rng.gen_range(10.0..60.0));
+                vec![
+                    min,
+                    Vec2::new(min.x + size.x, min.y),
+                    min + size,
+                    Vec2::new(min.x, min.y + size.y),
+                ]
+            })
+            .collect();
+
+        let mut obstacle_index = SpatialIndex::new(&arena);
+        for (i, polygon) in obstacles.iter().enumerate() {
+            obstacle_index.insert_polygon(entity(i), polygon);
+        }
+
+        let mut scene = Self {
+            config: SnakeConfig::default(),
+            laps,
+            bodies: vec![Vec::new(); snakes],
+            obstacles,
+            snake_index: SpatialIndex::new(&arena),
+            obstacle_index,
+        };
+        scene.move_snakes();
+        scene.index_snakes();
+        // Both ways have to find the same hits to be compared
+        assert_eq!(scene.indexed_hits(), scene.linear_hits());
+        scene
+    }
+
+    /// Move every snake forward by one tick, rewriting every point of the
+    /// bodies like the game does
+    fn move_snakes(&mut self) {
+        let step = self.config.movement_speed / self.config.tick_rate as f32;
+        let spacing = self.config.distance_between_points;
+
+        for (lap, body) in self.laps.iter_mut().zip(&mut self.bodies) {
+            lap.angle += step / lap.radius;
+            body.clear();
+            body.extend((0..BODY_POINTS).map(|i| {
+                let angle = lap.angle - i as f32 * spacing / lap.radius;
+                lap.center + Vec2::from_angle(angle) * lap.radius
+            }));
+        }
+    }
+
+    fn index_snakes(&mut self) {
+        for (i, body) in self.bodies.iter().enumerate() {
+            self.snake_index.insert_body(entity(i), body);
+        }
+    }
+
+    fn hits_segment(&self, head: Vec2, snake: usize, own: usize, part: usize) -> bool {
+        if snake == own && part < self.config.neck_segments {
+            return false;
+        }
+        let body = &self.bodies[snake];
+        distance_to_segment(&head, &body[part], &body[part + 1]) < self.config.snake_thickness
+    }
+
+    /// Snakes and obstacles hit by the heads, found through the indexes
+    fn indexed_hits(&self) -> usize {
+        let reach = self.config.snake_thickness;
+        let mut hits = 0;
+
+        for (own, body) in self.bodies.iter().enumerate() {
+            let head = body[0];
+            hits += self
+                .snake_index
+                .query(head, reach)
+                .iter()
+                .filter(|item| {
+                    self.hits_segment(head, item.entity.index() as usize, own, item.part)
+                })
+                .count();
+            hits += self
+                .obstacle_index
+                .query(head, 0.0)
+                .iter()
+                .filter(|item| {
+                    point_in_polygon(&head, &self.obstacles[item.entity.index() as usize])
+                })
+                .count();
+        }
+
+        hits
+    }
+
+    /// Snakes and obstacles hit by the heads, scanning all of them
+    fn linear_hits(&self) -> usize {
+        let mut hits = 0;
+
+        for (own, body) in self.bodies.iter().enumerate() {
+            let head = body[0];
+            for (snake, other) in self.bodies.iter().enumerate() {
+                hits += (0..other.len() - 1)
+                    .filter(|part| self.hits_segment(head, snake, own, *part))
+                    .count();
+            }
+            hits += self
+                .obstacles
+                .iter()
+                .filter(|polygon| point_in_polygon(&head, polygon))
+                .count();
+        }
+
+        hits
+    }
+}
+
+macro_rules! benches {
+    ($($snakes:literal => $update:ident, $indexed:ident, $linear:ident;)*) => {
+        $(
+            /// Moving the snakes and keeping the index up to date
+            #[bench]
+            fn $update(bencher: &mut Bencher) {
+                let mut scene = Scene::new($snakes);
+                bencher.iter(|| {
+                    scene.move_snakes();
+                    scene.index_snakes();
+                });
+            }
+
+            /// A whole tick with the indexes: moving, updating, querying
+            #[bench]
+            fn $indexed(bencher: &mut Bencher) {
+                let mut scene = Scene::new($snakes);
+                bencher.iter(|| {
+                    scene.move_snakes();
+                    scene.index_snakes();
+                    black_box(scene.indexed_hits())
+                });
+            }
+
+            /// A whole tick without the indexes: moving, scanning everything
+            #[bench]
+            fn $linear(bencher: &mut Bencher) {
+                let mut scene = Scene::new($snakes);
+                bencher.iter(|| {
+                    scene.move_snakes();
+                    black_box(scene.linear_hits())
+                });
+            }
+        )*
+    };
+}
+
+benches! {
+    4 => update_4_snakes, indexed_tick_4_snakes, linear_tick_4_snakes;
+    16 => update_16_snakes, indexed_tick_16_snakes, linear_tick_16_snakes;
+    64 => update_64_snakes, indexed_tick_64_snakes, linear_tick_64_snakes;
+    256 => update_256_snakes, indexed_tick_256_snakes, linear_tick_256_snakes;
+}
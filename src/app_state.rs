@@ -4,14 +4,35 @@ pub struct AppStatePlugin;
 
 impl Plugin for AppStatePlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<AppState>();
+        app.init_state::<AppState>()
+            .add_sub_state::<GamePhase>()
+            .add_systems(OnEnter(AppState::Restart), restart);
     }
 }
 
+fn restart(mut next_state: ResMut<NextState<AppState>>) {
+    next_state.set(AppState::InGame);
+}
+
 #[derive(States, Clone, Copy, Debug, PartialEq, Eq, Default, Hash, PartialOrd, Ord)]
 pub enum AppState {
     #[default]
     MainMenu,
     InGame,
     GameOver,
+    /// Goes straight back to `InGame`, so that the game is cleaned up and
+    /// set up again from scratch
+    Restart,
+}
+
+/// Phases of a game in progress
+#[derive(SubStates, Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+#[source(AppState = AppState::InGame)]
+pub enum GamePhase {
+    /// Everything is in place but frozen until the countdown ends
+    #[default]
+    Countdown,
+    Running,
+    /// Everything is frozen in place, the pause menu is shown
+    Paused,
 }
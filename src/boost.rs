@@ -0,0 +1,87 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{point_list::PointList, snake::Snake, steering_intent::SteeringIntent},
+    config::SnakeConfig,
+    food::spawn_pellet,
+    game::{follow_head, GameSet},
+};
+
+pub struct BoostPlugin;
+
+impl Plugin for BoostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                update_boost.in_set(GameSet::Steering),
+                burn_tail.in_set(GameSet::Movement).after(follow_head),
+            ),
+        );
+    }
+}
+
+/// Speed boost of the snake, paid with points burned from its tail
+#[derive(Component, Default)]
+pub struct Boost {
+    pub active: bool,
+    /// Fraction of a point already burned
+    burned: f32,
+}
+
+impl Boost {
+    /// Movement speed of the snake, faster while boosting
+    pub fn speed(&self, config: &SnakeConfig) -> f32 {
+        if self.active {
+            config.movement_speed * config.boost_speed_multiplier
+        } else {
+            config.movement_speed
+        }
+    }
+}
+
+/// Length under which a boost stops, a snake needs at least 2 points to be
+/// drawn whatever the config says
+fn min_length(config: &SnakeConfig) -> usize {
+    config.boost_min_length.max(2)
+}
+
+fn update_boost(
+    config: Res<SnakeConfig>,
+    mut query: Query<(&mut Boost, &PointList, &SteeringIntent), With<Snake>>,
+) {
+    for (mut boost, point_list, intent) in query.iter_mut() {
+        boost.active = intent.boost && point_list.0.len() > min_length(&config);
+        if !boost.active {
+            boost.burned = 0.0;
+        }
+    }
+}
+
+/// Remove points from the tail of boosting snakes, optionally dropping them
+/// as pellets along the path
+fn burn_tail(
+    mut commands: Commands,
+    time: Res<Time>,
+    config: Res<SnakeConfig>,
+    asset_server: Res<AssetServer>,
+    mut query: Query<(&mut Boost, &mut PointList), With<Snake>>,
+) {
+    for (mut boost, mut point_list) in query.iter_mut() {
+        if !boost.active {
+            continue;
+        }
+
+        boost.burned += config.boost_burn_rate * time.delta_seconds();
+        while boost.burned >= 1.0 && point_list.0.len() > min_length(&config) {
+            boost.burned -= 1.0;
+
+            let Some(tail) = point_list.0.pop() else {
+                break;
+            };
+            if config.boost_drop_food {
+                spawn_pellet(&mut commands, &asset_server, tail);
+            }
+        }
+    }
+}
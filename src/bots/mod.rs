@@ -0,0 +1,196 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+
+use crate::{
+    collision::point_in_polygon,
+    components::{
+        direction::Direction, obstacle::Obstacle, point_list::PointList, snake::Snake,
+        steering_intent::SteeringIntent,
+    },
+    config::SnakeConfig,
+    food::Food,
+    game::GameSet,
+    grid::{GridMovement, Turn},
+    levels::{
+        arena::{Arena, BoundaryMode},
+        MovementMode,
+    },
+};
+
+pub mod strategies;
+
+/// Distance bots look ahead for obstacles, walls and bodies
+const LOOKAHEAD: f32 = 30.0;
+
+/// Angle between the candidate headings a bot considers to avoid danger
+const AVOIDANCE_ANGLE: f32 = PI / 6.0;
+
+pub struct BotsPlugin;
+
+impl Plugin for BotsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(FixedUpdate, steer_bots.in_set(GameSet::Input));
+    }
+}
+
+/// Decides how a snake turns every tick
+pub trait SnakeController: Send + Sync {
+    /// Turn to apply this tick, from -1 (full right) to 1 (full left)
+    fn steer(&mut self, context: &ControllerContext) -> f32;
+}
+
+/// Snake driven by a [`SnakeController`] instead of a player
+#[derive(Component)]
+pub struct Bot(pub Box<dyn SnakeController>);
+
+/// What a controller knows about the world when taking its decision
+pub struct ControllerContext<'a> {
+    pub head: Vec2,
+    pub direction: f32,
+    pub points: &'a [Vec2],
+    pub foods: &'a [Vec2],
+    pub obstacles: &'a [&'a [Vec2]],
+    /// Bodies of the other snakes, head first
+    pub others: &'a [&'a [Vec2]],
+    pub arena: &'a Arena,
+    pub config: &'a SnakeConfig,
+    pub dt: f32,
+}
+
+impl ControllerContext<'_> {
+    /// Turn bringing the snake towards `angle` as fast as its turn speed allows
+    pub fn steer_towards(&self, angle: f32) -> f32 {
+        let difference = (angle - self.direction + PI).rem_euclid(TAU) - PI;
+        (difference / (self.config.turn_speed * self.dt)).clamp(-1.0, 1.0)
+    }
+
+    /// Turn bringing the snake towards `target`
+    pub fn steer_to(&self, target: Vec2) -> f32 {
+        self.steer_towards(self.arena.delta(self.head, target).to_angle())
+    }
+
+    /// Distance the snake can travel along `angle` before hitting something,
+    /// capped to the lookahead distance
+    pub fn free_distance(&self, angle: f32) -> f32 {
+        let heading = Vec2::from_angle(angle);
+        let step = self.config.snake_thickness;
+        let clearance = self.config.snake_thickness * 1.5;
+
+        let mut distance = step;
+        while distance < LOOKAHEAD {
+            let point = self.arena.wrap(self.head + heading * distance);
+
+            let outside = self.arena.boundary == BoundaryMode::Solid && !self.arena.contains(point);
+            let in_obstacle = self
+                .obstacles
+                .iter()
+                .any(|polygon| point_in_polygon(&point, polygon));
+            let near_body = self
+                .others
+                .iter()
+                .copied()
+                .chain(std::iter::once(
+                    self.points
+                        .get(self.config.neck_segments..)
+                        .unwrap_or_default(),
+                ))
+                .flatten()
+                .any(|body_point| self.arena.delta(point, *body_point).length() < clearance);
+
+            if outside || in_obstacle || near_body {
+                return distance;
+            }
+            distance += step;
+        }
+
+        LOOKAHEAD
+    }
+
+    /// Keep the preferred turn when its heading is clear, otherwise pick the
+    /// clear heading closest to it, or the least obstructed one
+    pub fn avoid(&self, preferred: f32) -> f32 {
+        let preferred_angle = self.direction + preferred * AVOIDANCE_ANGLE;
+        if self.free_distance(preferred_angle) >= LOOKAHEAD {
+            return preferred;
+        }
+
+        let candidates = [-1.0, -0.5, 0.0, 0.5, 1.0].map(|turn: f32| {
+            let distance = self.free_distance(self.direction + turn * AVOIDANCE_ANGLE);
+            (turn, distance)
+        });
+
+        candidates
+            .iter()
+            .filter(|(_, distance)| *distance >= LOOKAHEAD)
+            .min_by(|(a, _), (b, _)| (a - preferred).abs().total_cmp(&(b - preferred).abs()))
+            .or_else(|| candidates.iter().max_by(|(_, a), (_, b)| a.total_cmp(b)))
+            .map(|(turn, _)| *turn)
+            .unwrap_or(preferred)
+    }
+}
+
+type BotQuery<'world, 'state, 'a, 'b, 'c, 'd, 'e> = Query<
+    'world,
+    'state,
+    (
+        Entity,
+        &'a mut Bot,
+        &'b mut SteeringIntent,
+        &'c Direction,
+        &'d GridMovement,
+        &'e PointList,
+    ),
+    With<Snake>,
+>;
+
+/// Let every bot decide its turn, and hand it over like a player input would
+#[allow(clippy::too_many_arguments)]
+fn steer_bots(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    movement_mode: Res<MovementMode>,
+    mut bot_query: BotQuery,
+    snake_query: Query<(Entity, &PointList), With<Snake>>,
+    food_query: Query<&Transform, With<Food>>,
+    obstacle_query: Query<&PointList, (With<Obstacle>, Without<Snake>)>,
+) {
+    let dt = time.delta_seconds();
+    let foods: Vec<Vec2> = food_query.iter().map(|t| t.translation.xy()).collect();
+    let obstacles: Vec<&[Vec2]> = obstacle_query.iter().map(|p| p.0.as_slice()).collect();
+
+    for (entity, mut bot, mut intent, direction, grid_movement, point_list) in bot_query.iter_mut()
+    {
+        let others: Vec<&[Vec2]> = snake_query
+            .iter()
+            .filter(|(other, _)| *other != entity)
+            .map(|(_, other_point_list)| other_point_list.0.as_slice())
+            .collect();
+
+        let context = ControllerContext {
+            head: point_list.0[0],
+            direction: direction.0,
+            points: &point_list.0,
+            foods: &foods,
+            obstacles: &obstacles,
+            others: &others,
+            arena: &arena,
+            config: &config,
+            dt,
+        };
+        let amount = bot.0.steer(&context);
+
+        intent.turn = amount;
+        if *movement_mode == MovementMode::Grid
+            && !grid_movement.has_queued_turns()
+            && !intent.has_turn_requests()
+        {
+            if amount > 0.5 {
+                intent.request_turn(Turn::Left);
+            } else if amount < -0.5 {
+                intent.request_turn(Turn::Right);
+            }
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::{ControllerContext, SnakeController};
+
+/// Pick a strategy for the n-th bot, cycling through the built-in ones
+pub fn for_bot(index: usize) -> Box<dyn SnakeController> {
+    match index % 3 {
+        0 => Box::new(GreedyFood),
+        1 => Box::new(Wanderer::new(index as u64)),
+        _ => Box::new(Interceptor),
+    }
+}
+
+/// Distance ahead of its target's head the interceptor aims at
+const INTERCEPT_LEAD: f32 = 20.0;
+
+/// Heads for the closest food
+pub struct GreedyFood;
+
+impl SnakeController for GreedyFood {
+    fn steer(&mut self, context: &ControllerContext) -> f32 {
+        let closest_food = context.foods.iter().min_by(|a, b| {
+            let a = context.arena.delta(context.head, **a).length_squared();
+            let b = context.arena.delta(context.head, **b).length_squared();
+            a.total_cmp(&b)
+        });
+
+        let preferred = closest_food.map_or(0.0, |food| context.steer_to(*food));
+        context.avoid(preferred)
+    }
+}
+
+/// Drifts around randomly while staying away from obstacles
+pub struct Wanderer {
+    rng: StdRng,
+    turn: f32,
+}
+
+impl Wanderer {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: StdRng::seed_from_u64(seed),
+            turn: 0.0,
+        }
+    }
+}
+
+impl SnakeController for Wanderer {
+    fn steer(&mut self, context: &ControllerContext) -> f32 {
+        self.turn = (self.turn + self.rng.gen_range(-0.2..0.2)).clamp(-1.0, 1.0);
+        context.avoid(self.turn)
+    }
+}
+
+/// Cuts the way of the closest snake by aiming ahead of its head
+pub struct Interceptor;
+
+impl SnakeController for Interceptor {
+    fn steer(&mut self, context: &ControllerContext) -> f32 {
+        let target = context
+            .others
+            .iter()
+            .filter(|body| body.len() >= 2)
+            .map(|body| {
+                let heading = context.arena.delta(body[1], body[0]).normalize_or_zero();
+                context.arena.wrap(body[0] + heading * INTERCEPT_LEAD)
+            })
+            .min_by(|a, b| {
+                let a = context.arena.delta(context.head, *a).length_squared();
+                let b = context.arena.delta(context.head, *b).length_squared();
+                a.total_cmp(&b)
+            });
+
+        let preferred = target.map_or(0.0, |target| context.steer_to(target));
+        context.avoid(preferred)
+    }
+}
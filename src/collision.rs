@@ -1,79 +1,237 @@
-use bevy::prelude::*;
+use bevy::{ecs::system::EntityCommands, prelude::*};
 
 use crate::{
-    app_state::AppState,
-    components::{obstacle::Obstacle, point_list::PointList, snake::Snake},
-    players_lifes::{setup_players_lifes, PlayersLifes},
+    components::{
+        invincible::Invincible,
+        obstacle::Obstacle,
+        player::Player,
+        point_list::PointList,
+        power_ups::{Ghost, Shield, Shrink},
+        snake::Snake,
+    },
+    config::SnakeConfig,
+    food::spawn_pellet,
+    game::GameSet,
+    levels::arena::{Arena, BoundaryMode},
+    players_lifes::{PlayersKills, PlayersLifes},
+    power_ups,
+    respawn::Respawn,
+    spatial::SpatialIndex,
 };
 
-#[derive(Component)]
-pub struct InvincibilityTimer {
-    pub timer: Timer,
+/// A snake ran into something this tick
+#[derive(Event, Debug)]
+pub struct SnakeHit {
+    pub snake: Entity,
+    /// Player whose snake caused the hit, if any
+    pub by: Option<Player>,
 }
 
 pub struct CollisionPlugin;
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (collision_system, invincibility_timer_system)
-                .after(setup_players_lifes)
-                .run_if(in_state(AppState::InGame)),
+        app.add_event::<SnakeHit>().add_systems(
+            FixedUpdate,
+            (collision_system, apply_hits, invincibility_timer_system)
+                .chain()
+                .in_set(GameSet::Collision),
         );
     }
 }
 
-type SnakeQuery<'world, 'state, 'point_list> =
-    Query<'world, 'state, (&'point_list mut PointList, Entity), (With<Snake>, Without<Obstacle>)>;
+type SnakeQuery<'world, 'state, 'point_list, 'player> = Query<
+    'world,
+    'state,
+    (
+        &'point_list PointList,
+        &'player Player,
+        Entity,
+        Has<Invincible>,
+        Has<Ghost>,
+        Has<Shrink>,
+    ),
+    (With<Snake>, Without<Obstacle>),
+>;
 
 type ObstacleQuery<'world, 'state, 'point_list> =
     Query<'world, 'state, (&'point_list PointList, Entity), (With<Obstacle>, Without<Snake>)>;
 
+/// Detect every hit of the tick before applying any of them, so that snakes
+/// crashing into each other are all hit at once. Invincible snakes can't be
+/// hit, but the others still collide with them. Ghosts go through obstacles
+/// and shrunk snakes need a closer contact.
+#[allow(clippy::too_many_arguments)]
 pub fn collision_system(
+    snake_query: SnakeQuery,
+    obstacle_query: ObstacleQuery,
+    snake_index: Res<SpatialIndex<Snake>>,
+    obstacle_index: Res<SpatialIndex<Obstacle>>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    mut hits: EventWriter<SnakeHit>,
+) {
+    for (snake_point_list, _, snake_entity, invincible, ghost, shrunk) in snake_query.iter() {
+        if invincible {
+            continue;
+        }
+
+        let head = snake_point_list.0[0];
+        let thickness = power_ups::thickness(shrunk, &config);
+
+        let hit_obstacle = !ghost
+            && obstacle_index.query(head, 0.0).iter().any(|item| {
+                obstacle_query
+                    .get(item.entity)
+                    .is_ok_and(|(obstacle_point_list, _)| {
+                        point_in_polygon(&head, &obstacle_point_list.0)
+                    })
+            });
+        let hit_wall = arena.boundary == BoundaryMode::Solid && !arena.contains(head);
+
+        // Crashing into a wall, an obstacle, itself or head-on gives no
+        // credit, even when touching the body of another snake as well
+        let mut uncredited = hit_obstacle || hit_wall;
+        let mut credited = None;
+
+        for item in snake_index.query(head, config.snake_thickness) {
+            let Ok((other_point_list, other_player, other_entity, _, _, other_shrunk)) =
+                snake_query.get(item.entity)
+            else {
+                continue;
+            };
+            let Some(segment) = other_point_list.0.get(item.part..=item.part + 1) else {
+                continue;
+            };
+
+            // Half of each thickness, the sides of the snakes have to touch
+            let reach = (thickness + power_ups::thickness(other_shrunk, &config)) / 2.0;
+
+            if other_entity == snake_entity {
+                uncredited |= item.part >= config.neck_segments
+                    && head_hits_segment(&head, segment, reach, &arena);
+            } else if item.part == 0 && arena.delta(head, segment[0]).length() < reach {
+                // Head-on crash, both snakes get hit
+                uncredited = true;
+            } else if head_hits_segment(&head, segment, reach, &arena) {
+                // Running into the body of another snake, its player gets the credit
+                credited = credited.or(Some(*other_player));
+            }
+        }
+
+        if uncredited || credited.is_some() {
+            hits.send(SnakeHit {
+                snake: snake_entity,
+                by: if uncredited { None } else { credited },
+            });
+        }
+    }
+}
+
+type HitSnakeQuery<'world, 'state, 'a> =
+    Query<'world, 'state, (&'a mut PointList, &'a Player, Has<Shield>), With<Snake>>;
+
+/// Remove a life from the hit snakes, dead snakes dissolve into food and the
+/// others respawn or lose a few points. A shield absorbs the hit instead.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_hits(
     mut commands: Commands,
-    mut snake_query: SnakeQuery,
-    mut obstacle_query: ObstacleQuery,
+    mut hits: EventReader<SnakeHit>,
+    mut respawns: EventWriter<Respawn>,
+    mut snake_query: HitSnakeQuery,
     mut lifes_query: ResMut<PlayersLifes>,
-    invincibility_query: Query<&InvincibilityTimer>,
+    mut kills: ResMut<PlayersKills>,
+    asset_server: Res<AssetServer>,
+    config: Res<SnakeConfig>,
 ) {
-    if !invincibility_query.is_empty() {
-        return;
-    }
+    for hit in hits.read() {
+        let Ok((mut snake_point_list, player, shielded)) = snake_query.get_mut(hit.snake) else {
+            continue;
+        };
 
-    let (mut snake_point_list, _) = snake_query.single_mut();
+        // The shield breaks, leaving a moment to get out of trouble
+        if shielded {
+            let mut snake = commands.entity(hit.snake);
+            snake.remove::<Shield>();
+            give_invincibility(&mut snake, config.invincibility_duration);
+            continue;
+        }
 
-    for (obstacle_point_list, _) in obstacle_query.iter_mut() {
-        if point_in_polygon(&snake_point_list.0[0], &obstacle_point_list.0) {
-            // Remove one life
-            // Add invincibility for 3 seconds
-            // Remove N points from the snake
-            lifes_query.0 -= 1;
-            commands.spawn(InvincibilityTimer {
-                timer: Timer::from_seconds(3.0, TimerMode::Once),
-            });
-            let snake_length = snake_point_list.0.len();
-            snake_point_list.truncate(snake_length - 3);
+        // Remove one life, the snake is gone once the player has none left
+        let lifes = &mut lifes_query.0[player.0];
+        *lifes = lifes.saturating_sub(1);
+        if *lifes == 0 {
+            if let Some(by) = hit.by {
+                kills.0[by.0] += 1;
+            }
+            for point in snake_point_list
+                .0
+                .iter()
+                .step_by(config.dissolve_food_spacing.max(1))
+            {
+                spawn_pellet(&mut commands, &asset_server, *point);
+            }
+            commands.entity(hit.snake).despawn_recursive();
+            continue;
+        }
 
-            break;
+        // Rebuilt somewhere safe, with its own spawn protection
+        if config.respawn {
+            respawns.send(Respawn { snake: hit.snake });
+            continue;
         }
+
+        // Remove N points from the snake, keeping at least one segment
+        let snake_length = snake_point_list.0.len();
+        snake_point_list.truncate(
+            snake_length
+                .saturating_sub(config.hit_truncate_points)
+                .max(2),
+        );
+
+        give_invincibility(
+            &mut commands.entity(hit.snake),
+            config.invincibility_duration,
+        );
     }
 }
 
+/// Add invincibility for this many seconds, to this snake only, refreshing
+/// the one it already has
+pub(crate) fn give_invincibility(snake: &mut EntityCommands, seconds: f32) {
+    snake.add(
+        move |mut snake: EntityWorldMut| match snake.get_mut::<Invincible>() {
+            Some(mut invincible) => invincible.refresh(seconds),
+            None => {
+                snake.insert(Invincible::new(seconds));
+            }
+        },
+    );
+}
+
+/// Make invincible snakes blink until their invincibility wears off
 fn invincibility_timer_system(
     mut commands: Commands,
     time: Res<Time>,
-    mut invincibility_query: Query<(Entity, &mut InvincibilityTimer)>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut invincibility_query: Query<(Entity, &mut Invincible, &Handle<ColorMaterial>)>,
 ) {
-    for (entity, mut invincibility_timer) in invincibility_query.iter_mut() {
-        invincibility_timer.timer.tick(time.delta());
-        if invincibility_timer.timer.finished() {
-            commands.entity(entity).despawn();
+    for (entity, mut invincible, material) in invincibility_query.iter_mut() {
+        invincible.timer.tick(time.delta());
+
+        let alpha = if invincible.timer.finished() {
+            commands.entity(entity).remove::<Invincible>();
+            1.0
+        } else {
+            invincible.alpha()
+        };
+        if let Some(material) = materials.get_mut(material) {
+            material.color.set_alpha(alpha);
         }
     }
 }
 
-fn point_in_polygon(point: &Vec2, polygon: &[Vec2]) -> bool {
+pub fn point_in_polygon(point: &Vec2, polygon: &[Vec2]) -> bool {
     let mut is_inside = false;
     let mut j = polygon.len() - 1;
 
@@ -92,3 +250,24 @@ fn point_in_polygon(point: &Vec2, polygon: &[Vec2]) -> bool {
 
     is_inside
 }
+
+/// Check if a head touches a segment of a snake body. When testing a snake
+/// against itself, the first segments behind the head have to be skipped,
+/// otherwise any tight turn would be detected as a hit.
+fn head_hits_segment(head: &Vec2, segment: &[Vec2], reach: f32, arena: &Arena) -> bool {
+    // Bring the segment next to the head, in case it lies across the seam
+    let a = *head + arena.delta(*head, segment[0]);
+    let b = a + arena.delta(segment[0], segment[1]);
+    distance_to_segment(head, &a, &b) < reach
+}
+
+pub fn distance_to_segment(point: &Vec2, a: &Vec2, b: &Vec2) -> f32 {
+    let ab = *b - *a;
+    let length_squared = ab.length_squared();
+    if length_squared == 0.0 {
+        return point.distance(*a);
+    }
+
+    let t = ((*point - *a).dot(ab) / length_squared).clamp(0.0, 1.0);
+    point.distance(*a + ab * t)
+}
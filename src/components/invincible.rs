@@ -0,0 +1,37 @@
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+/// Times per second an invincible snake blinks
+const BLINK_FREQUENCY: f32 = 4.0;
+
+/// Snake that can't be hit for a while, after a collision, a respawn or
+/// thanks to a power-up. The snake blinks until it wears off.
+#[derive(Component, Debug)]
+pub struct Invincible {
+    pub timer: Timer,
+}
+
+impl Invincible {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+
+    /// Keep the longest of the remaining and the new duration
+    pub fn refresh(&mut self, seconds: f32) {
+        if self.timer.remaining_secs() < seconds {
+            *self = Self::new(seconds);
+        }
+    }
+
+    /// Opacity of the snake at this point of the blinking
+    pub fn alpha(&self) -> f32 {
+        if (self.timer.elapsed_secs() * BLINK_FREQUENCY * TAU).cos() > 0.0 {
+            1.0
+        } else {
+            0.3
+        }
+    }
+}
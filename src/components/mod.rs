@@ -1,4 +1,10 @@
 pub mod direction;
+pub mod invincible;
 pub mod obstacle;
+pub mod path_history;
+pub mod pending_growth;
+pub mod player;
 pub mod point_list;
+pub mod power_ups;
 pub mod snake;
+pub mod steering_intent;
@@ -0,0 +1,95 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::levels::arena::Arena;
+
+/// Positions successively taken by the head of the snake, most recent first.
+///
+/// The body points are sampled along this path at exact multiples of the
+/// distance between points, so the body traces exactly where the head went.
+#[derive(Component, Debug)]
+pub struct PathHistory(VecDeque<Vec2>);
+
+impl PathHistory {
+    pub fn from_points(points: &[Vec2]) -> Self {
+        Self(points.iter().copied().collect())
+    }
+
+    /// Oldest recorded position
+    pub fn end(&self) -> Option<Vec2> {
+        self.0.back().copied()
+    }
+
+    /// Total length of the recorded path
+    pub fn length(&self, arena: &Arena) -> f32 {
+        self.0
+            .iter()
+            .zip(self.0.iter().skip(1))
+            .map(|(from, to)| arena.delta(*from, *to).length())
+            .sum()
+    }
+
+    /// Record the new position of the head
+    pub fn record(&mut self, head: Vec2) {
+        self.0.push_front(head);
+    }
+
+    /// Place `points` every `spacing` along the path, starting from the head.
+    /// Points past the end of the recorded path stay on its last position.
+    pub fn sample(&self, points: &mut [Vec2], spacing: f32, arena: &Arena) {
+        let Some(&head) = self.0.front() else {
+            return;
+        };
+
+        let mut segments = self.0.iter().zip(self.0.iter().skip(1));
+        // Segment currently walked, with the path length at its start
+        let mut current = segments
+            .next()
+            .map(|(from, to)| (*from, arena.delta(*from, *to)));
+        let mut walked = 0.0;
+        let mut last = head;
+
+        for (i, point) in points.iter_mut().enumerate() {
+            let target = i as f32 * spacing;
+
+            *point = loop {
+                let Some((from, delta)) = current else {
+                    break last;
+                };
+
+                let length = delta.length();
+                if walked + length >= target {
+                    let t = if length > 0.0 {
+                        (target - walked) / length
+                    } else {
+                        0.0
+                    };
+                    break arena.wrap(from + delta * t);
+                }
+
+                walked += length;
+                last = arena.wrap(from + delta);
+                current = segments
+                    .next()
+                    .map(|(from, to)| (*from, arena.delta(*from, *to)));
+            };
+        }
+    }
+
+    /// Forget the part of the path lying further than `length` from the head
+    pub fn trim(&mut self, length: f32, arena: &Arena) {
+        let mut walked = 0.0;
+        let mut keep = self.0.len();
+
+        for (i, (from, to)) in self.0.iter().zip(self.0.iter().skip(1)).enumerate() {
+            walked += arena.delta(*from, *to).length();
+            if walked >= length {
+                keep = i + 2;
+                break;
+            }
+        }
+
+        self.0.truncate(keep);
+    }
+}
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+
+/// Points the snake still has to grow by.
+///
+/// The tail holds still while the head moves forward, until the owed length
+/// has been paid out. Anything making the snake grow goes through this.
+#[derive(Component, Default)]
+pub struct PendingGrowth(pub usize);
+
+impl PendingGrowth {
+    pub fn add(&mut self, points: usize) {
+        self.0 += points;
+    }
+}
@@ -0,0 +1,65 @@
+use bevy::color::palettes::tailwind;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of local players
+pub const MAX_PLAYERS: usize = 4;
+
+/// Index of the player controlling the snake
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Player(pub usize);
+
+/// Keys steering the snake of a player
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct PlayerControls {
+    pub left: KeyCode,
+    pub right: KeyCode,
+    pub boost: KeyCode,
+}
+
+/// Everything that differs from one player to the other
+pub struct PlayerPreset {
+    pub name: &'static str,
+    pub color: Srgba,
+    /// Default keys, until rebound
+    pub controls: PlayerControls,
+}
+
+pub const PLAYER_PRESETS: [PlayerPreset; MAX_PLAYERS] = [
+    PlayerPreset {
+        name: "P1",
+        color: tailwind::GREEN_500,
+        controls: PlayerControls {
+            left: KeyCode::ArrowLeft,
+            right: KeyCode::ArrowRight,
+            boost: KeyCode::ArrowUp,
+        },
+    },
+    PlayerPreset {
+        name: "P2",
+        color: tailwind::SKY_500,
+        controls: PlayerControls {
+            left: KeyCode::KeyA,
+            right: KeyCode::KeyD,
+            boost: KeyCode::KeyW,
+        },
+    },
+    PlayerPreset {
+        name: "P3",
+        color: tailwind::AMBER_500,
+        controls: PlayerControls {
+            left: KeyCode::KeyJ,
+            right: KeyCode::KeyL,
+            boost: KeyCode::KeyI,
+        },
+    },
+    PlayerPreset {
+        name: "P4",
+        color: tailwind::FUCHSIA_500,
+        controls: PlayerControls {
+            left: KeyCode::Numpad4,
+            right: KeyCode::Numpad6,
+            boost: KeyCode::Numpad8,
+        },
+    },
+];
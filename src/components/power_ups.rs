@@ -0,0 +1,79 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+
+/// Longest time a power-up can be stacked up to
+const MAX_DURATION: f32 = 30.0;
+
+/// Timed effect collected by eating some foods
+pub trait PowerUp: Component + Sized {
+    /// Name shown in the HUD
+    const LABEL: &'static str;
+
+    fn new(seconds: f32) -> Self;
+
+    fn timer(&self) -> &Timer;
+
+    fn timer_mut(&mut self) -> &mut Timer;
+
+    /// Add the duration of another pickup to the remaining time
+    fn stack(&mut self, seconds: f32) {
+        let remaining = (self.timer().remaining_secs() + seconds).min(MAX_DURATION.max(seconds));
+        *self.timer_mut() = Timer::from_seconds(remaining, TimerMode::Once);
+    }
+
+    /// Give the power-up to the snake, stacking it when it already has it
+    fn grant(snake: &mut EntityCommands, seconds: f32) {
+        snake.add(
+            move |mut snake: EntityWorldMut| match snake.get_mut::<Self>() {
+                Some(mut power_up) => power_up.stack(seconds),
+                None => {
+                    snake.insert(Self::new(seconds));
+                }
+            },
+        );
+    }
+}
+
+macro_rules! power_ups {
+    ($($(#[$doc:meta])* $name:ident => $label:literal,)*) => {
+        $(
+            $(#[$doc])*
+            #[derive(Component, Debug)]
+            pub struct $name {
+                pub timer: Timer,
+            }
+
+            impl PowerUp for $name {
+                const LABEL: &'static str = $label;
+
+                fn new(seconds: f32) -> Self {
+                    Self {
+                        timer: Timer::from_seconds(seconds, TimerMode::Once),
+                    }
+                }
+
+                fn timer(&self) -> &Timer {
+                    &self.timer
+                }
+
+                fn timer_mut(&mut self) -> &mut Timer {
+                    &mut self.timer
+                }
+            }
+        )*
+    };
+}
+
+power_ups! {
+    /// The snake goes through obstacles
+    Ghost => "GHOST",
+    /// Every other snake moves slower
+    SlowMotion => "SLOW-MO",
+    /// The snake is thinner, to slip through tight gaps
+    Shrink => "SHRINK",
+    /// Foods around the head are pulled toward it
+    Magnet => "MAGNET",
+    /// Foods eaten are worth twice their score
+    DoubleScore => "x2 SCORE",
+    /// The next hit is absorbed without losing a life
+    Shield => "SHIELD",
+}
@@ -1,53 +1,89 @@
-use bevy::color::palettes::tailwind;
 use bevy::prelude::*;
 use bevy::render::mesh::Indices;
 use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::render_resource::PrimitiveTopology;
 use bevy::sprite::MaterialMesh2dBundle;
 
-use crate::consts::{DISTANCE_BETWEEN_POINTS, INITIAL_LENGTH, SNAKE_THICKNESS};
+use crate::boost::Boost;
+use crate::config::SnakeConfig;
+use crate::grid::GridMovement;
+use crate::levels::arena::Arena;
 
 use super::direction::Direction;
+use super::path_history::PathHistory;
+use super::pending_growth::PendingGrowth;
 use super::point_list::PointList;
+use super::steering_intent::SteeringIntent;
 
 #[derive(Component)]
 pub struct Snake;
 
 impl Snake {
-    pub fn get_indices_and_vertices(point_list: &PointList) -> (Vec<u32>, Vec<[f32; 3]>) {
+    pub fn get_indices_and_vertices(
+        point_list: &PointList,
+        thickness: f32,
+        arena: &Arena,
+    ) -> (Vec<u32>, Vec<[f32; 3]>) {
         let mut vertices = Vec::new();
         let mut indices = Vec::new();
+        let points = &point_list.0;
 
-        for i in 0..point_list.0.len() {
-            let point = point_list.0[i];
-            let normal = if i < point_list.0.len() - 1 {
-                // Calculate normal for the current segment
-                (point_list.0[i + 1] - point).perp().normalize() * SNAKE_THICKNESS / 2.0
+        let edges = |point: Vec2, direction: Vec2| {
+            let normal = direction.perp().normalize_or_zero() * thickness / 2.0;
+            [
+                [point.x - normal.x, point.y - normal.y, 0.0],
+                [point.x + normal.x, point.y + normal.y, 0.0],
+            ]
+        };
+        let quad = |indices: &mut Vec<u32>, from: u32, to: u32| {
+            indices.extend_from_slice(&[from, from + 1, to, to, from + 1, to + 1]);
+        };
+
+        for i in 0..points.len() {
+            let point = points[i];
+            let direction = if i < points.len() - 1 {
+                // Calculate direction of the current segment
+                arena.delta(point, points[i + 1])
             } else {
-                // Use the normal from the previous segment for the last point
-                (point - point_list.0[i - 1]).perp().normalize() * SNAKE_THICKNESS / 2.0
+                // Use the direction of the previous segment for the last point
+                arena.delta(points[i - 1], point)
             };
 
             // Add two vertices for the edges of the snake
-            vertices.push([point.x - normal.x, point.y - normal.y, 0.0]);
-            vertices.push([point.x + normal.x, point.y + normal.y, 0.0]);
+            vertices.extend_from_slice(&edges(point, direction));
         }
 
-        // Create indices for the triangle strip
-        for i in 0..(vertices.len() as u32 / 2 - 1) {
-            let base = i * 2;
-            indices.extend_from_slice(&[base, base + 1, base + 2, base + 2, base + 1, base + 3]);
+        // Create two triangles per segment
+        for i in 0..points.len().saturating_sub(1) {
+            let base = i as u32 * 2;
+            let delta = arena.delta(points[i], points[i + 1]);
+
+            if delta == points[i + 1] - points[i] {
+                quad(&mut indices, base, base + 2);
+                continue;
+            }
+
+            // The segment crosses the arena seam: draw it on both sides of
+            // the arena, each half leaving through the edge
+            let ghost_after = vertices.len() as u32;
+            vertices.extend_from_slice(&edges(points[i] + delta, delta));
+            quad(&mut indices, base, ghost_after);
+
+            let ghost_before = vertices.len() as u32;
+            vertices.extend_from_slice(&edges(points[i + 1] - delta, delta));
+            quad(&mut indices, ghost_before, base + 2);
         }
 
         (indices, vertices)
     }
 
-    pub fn create_mesh(point_list: &PointList) -> Mesh {
+    pub fn create_mesh(point_list: &PointList, arena: &Arena, config: &SnakeConfig) -> Mesh {
         let mut mesh = Mesh::new(
-            PrimitiveTopology::TriangleStrip,
+            PrimitiveTopology::TriangleList,
             RenderAssetUsages::default(),
         );
-        let (indices, vertices) = Self::get_indices_and_vertices(point_list);
+        let (indices, vertices) =
+            Self::get_indices_and_vertices(point_list, config.snake_thickness, arena);
 
         mesh.insert_indices(Indices::U32(indices));
         mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
@@ -55,31 +91,57 @@ impl Snake {
         mesh
     }
 
-    pub fn get_default_entity_components(
+    /// Points of a straight snake with its head at `head`, facing `direction`
+    pub fn initial_points(
+        head: Vec2,
+        direction: f32,
+        arena: &Arena,
+        config: &SnakeConfig,
+    ) -> Vec<Vec2> {
+        let backward = -Vec2::from_angle(direction);
+
+        (0..config.initial_length)
+            .map(|i| arena.wrap(head + backward * i as f32 * config.distance_between_points))
+            .collect()
+    }
+
+    /// Create a straight snake with its head at `head`, facing `direction`
+    pub fn get_entity_components(
+        head: Vec2,
+        direction: f32,
+        color: Color,
         meshes: &mut ResMut<Assets<Mesh>>,
         materials: &mut ResMut<Assets<ColorMaterial>>,
+        arena: &Arena,
+        config: &SnakeConfig,
     ) -> (
         Self,
         PointList,
+        PathHistory,
         Direction,
+        Boost,
+        GridMovement,
+        PendingGrowth,
+        SteeringIntent,
         MaterialMesh2dBundle<ColorMaterial>,
     ) {
-        let mut points = Vec::new();
-
-        for i in 0..INITIAL_LENGTH {
-            points.push(Vec2::new(i as f32 * DISTANCE_BETWEEN_POINTS, 0.0));
-        }
-
+        let points = Self::initial_points(head, direction, arena, config);
+        let path_history = PathHistory::from_points(&points);
         let point_list = PointList(points);
-        let mesh = Self::create_mesh(&point_list);
+        let mesh = Self::create_mesh(&point_list, arena, config);
 
         (
             Snake,
             point_list,
-            Direction::default(),
+            path_history,
+            Direction(direction),
+            Boost::default(),
+            GridMovement::default(),
+            PendingGrowth::default(),
+            SteeringIntent::default(),
             MaterialMesh2dBundle {
                 mesh: meshes.add(mesh).into(),
-                material: materials.add(Color::from(tailwind::GREEN_500)),
+                material: materials.add(color),
                 ..Default::default()
             },
         )
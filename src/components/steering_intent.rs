@@ -0,0 +1,37 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::grid::Turn;
+
+/// Number of discrete turns kept until the movement systems consume them
+const MAX_TURN_REQUESTS: usize = 3;
+
+/// What the controller of a snake wants it to do.
+///
+/// Input sources (keyboard, bots, ...) fill it in, the movement systems only
+/// ever read from it, so a snake can be driven by anything.
+#[derive(Component, Default, Debug)]
+pub struct SteeringIntent {
+    /// Analog turn, from -1 (full right) to 1 (full left)
+    pub turn: f32,
+    pub boost: bool,
+    /// Discrete turns, used by the grid movement mode
+    turn_requests: VecDeque<Turn>,
+}
+
+impl SteeringIntent {
+    pub fn request_turn(&mut self, turn: Turn) {
+        if self.turn_requests.len() < MAX_TURN_REQUESTS {
+            self.turn_requests.push_back(turn);
+        }
+    }
+
+    pub fn has_turn_requests(&self) -> bool {
+        !self.turn_requests.is_empty()
+    }
+
+    pub fn take_turn_requests(&mut self) -> impl Iterator<Item = Turn> + '_ {
+        self.turn_requests.drain(..)
+    }
+}
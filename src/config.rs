@@ -0,0 +1,203 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::json_asset::{JsonAsset, JsonAssetHandle, JsonAssetPlugin};
+
+pub struct SnakeConfigPlugin;
+
+impl Plugin for SnakeConfigPlugin {
+    fn build(&self, app: &mut App) {
+        let config = SnakeConfig::default();
+
+        app.add_plugins(JsonAssetPlugin::<SnakeConfig>::default())
+            .insert_resource(Time::<Fixed>::from_hz(config.tick_rate))
+            .insert_resource(config)
+            .add_systems(Update, apply_snake_config);
+    }
+}
+
+/// Tuning values of the snake simulation.
+///
+/// Loaded from `assets/snake.config.json` and reloaded whenever the file
+/// changes on disk, the default values are used until the file is loaded.
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+#[serde(default)]
+pub struct SnakeConfig {
+    /// Number of gameplay ticks simulated per second in `FixedUpdate`
+    pub tick_rate: f64,
+    pub distance_between_points: f32,
+    pub initial_length: u32,
+    pub snake_thickness: f32,
+    /// Segments behind the head ignored by self collision
+    pub neck_segments: usize,
+    /// Turn speed in radians per second
+    pub turn_speed: f32,
+    pub movement_speed: f32,
+    /// Distance between the head and a food under which the food is eaten
+    pub food_pickup_radius: f32,
+    /// Seconds during which the snake can't be hit again after a collision
+    pub invincibility_duration: f32,
+    /// Points removed from the snake on collision
+    pub hit_truncate_points: usize,
+    /// Points added to the snake for each pellet eaten
+    pub pellet_growth_points: usize,
+    /// Factor applied to the movement speed while boosting
+    pub boost_speed_multiplier: f32,
+    /// Tail points burned per second while boosting
+    pub boost_burn_rate: f32,
+    /// Length under which the snake can't boost anymore
+    pub boost_min_length: usize,
+    /// Whether burned points are dropped as pellets
+    pub boost_drop_food: bool,
+    /// A dead snake drops a pellet every this many points
+    pub dissolve_food_spacing: usize,
+    /// Seconds of countdown before the snakes start moving
+    pub countdown_duration: f32,
+    /// Lifes of each player at the start of a game
+    pub starting_lifes: u32,
+    /// Whether a snake losing a life is rebuilt at a safe place, instead of
+    /// losing a few points where it is
+    pub respawn: bool,
+    /// Seconds during which a respawned snake can't be hit
+    pub spawn_protection_duration: f32,
+    /// Score of a pellet, the score of the other foods depends on their type
+    pub pellet_score: u32,
+    /// Combo multiplier gained with each food eaten
+    pub combo_step: f32,
+    /// Highest combo multiplier
+    pub combo_max: f32,
+    /// Combo multiplier lost per second, down to 1
+    pub combo_decay: f32,
+    /// Points added to the score of a food for each point of the snake body
+    pub length_bonus: f32,
+}
+
+impl Default for SnakeConfig {
+    fn default() -> Self {
+        Self {
+            tick_rate: 64.0,
+            distance_between_points: 2.0,
+            initial_length: 40,
+            snake_thickness: 3.0,
+            neck_segments: 5,
+            turn_speed: 3.0,
+            movement_speed: 20.0,
+            food_pickup_radius: 10.0,
+            invincibility_duration: 3.0,
+            hit_truncate_points: 3,
+            pellet_growth_points: 1,
+            boost_speed_multiplier: 2.0,
+            boost_burn_rate: 4.0,
+            boost_min_length: 10,
+            boost_drop_food: true,
+            dissolve_food_spacing: 4,
+            countdown_duration: 3.0,
+            starting_lifes: 3,
+            respawn: true,
+            spawn_protection_duration: 2.0,
+            pellet_score: 1,
+            combo_step: 0.5,
+            combo_max: 4.0,
+            combo_decay: 1.0,
+            length_bonus: 0.1,
+        }
+    }
+}
+
+impl JsonAsset for SnakeConfig {
+    const PATH: &'static str = "snake.config.json";
+    const EXTENSION: &'static str = "config.json";
+
+    /// Reject the values the game can't work with, like a tick rate of zero
+    /// or negative durations that would make the timers panic
+    fn validate(&self) -> Result<(), String> {
+        if !self.tick_rate.is_finite() || self.tick_rate <= 0.0 {
+            return Err(format!("invalid tick_rate {}", self.tick_rate));
+        }
+        if self.initial_length < 2 {
+            return Err(format!(
+                "invalid initial_length {}, a snake needs at least 2 points",
+                self.initial_length
+            ));
+        }
+        if !self.distance_between_points.is_finite() || self.distance_between_points <= 0.0 {
+            return Err(format!(
+                "invalid distance_between_points {}",
+                self.distance_between_points
+            ));
+        }
+
+        let values = [
+            ("snake_thickness", self.snake_thickness),
+            ("turn_speed", self.turn_speed),
+            ("movement_speed", self.movement_speed),
+            ("food_pickup_radius", self.food_pickup_radius),
+            ("invincibility_duration", self.invincibility_duration),
+            ("boost_speed_multiplier", self.boost_speed_multiplier),
+            ("boost_burn_rate", self.boost_burn_rate),
+            ("countdown_duration", self.countdown_duration),
+            ("spawn_protection_duration", self.spawn_protection_duration),
+            ("combo_step", self.combo_step),
+            ("combo_max", self.combo_max),
+            ("combo_decay", self.combo_decay),
+            ("length_bonus", self.length_bonus),
+        ];
+        if let Some((field, value)) = values
+            .into_iter()
+            .find(|(_, value)| !value.is_finite() || *value < 0.0)
+        {
+            return Err(format!("invalid {field} {value}"));
+        }
+        Ok(())
+    }
+}
+
+/// Copy the loaded asset into the [`SnakeConfig`] resource every time the
+/// file is (re)loaded
+fn apply_snake_config(
+    mut events: EventReader<AssetEvent<SnakeConfig>>,
+    handle: Res<JsonAssetHandle<SnakeConfig>>,
+    configs: Res<Assets<SnakeConfig>>,
+    mut config: ResMut<SnakeConfig>,
+    mut fixed_time: ResMut<Time<Fixed>>,
+) {
+    let Some(loaded) = handle.reloaded(&mut events, &configs) else {
+        return;
+    };
+
+    *config = loaded.clone();
+    fixed_time.set_timestep_hz(config.tick_rate);
+    info!("Snake config loaded: {:?}", *config);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_config_is_valid() {
+        let config: SnakeConfig =
+            serde_json::from_str(include_str!("../assets/snake.config.json")).unwrap();
+        assert_eq!(config.validate(), Ok(()));
+        assert_eq!(SnakeConfig::default().validate(), Ok(()));
+    }
+
+    #[test]
+    fn crashing_values_are_rejected() {
+        let invalid = [
+            r#"{ "tick_rate": 0.0 }"#,
+            r#"{ "tick_rate": -64.0 }"#,
+            r#"{ "initial_length": 1 }"#,
+            r#"{ "distance_between_points": 0.0 }"#,
+            r#"{ "invincibility_duration": -1.0 }"#,
+            r#"{ "countdown_duration": -3.0 }"#,
+            r#"{ "spawn_protection_duration": -2.0 }"#,
+            r#"{ "movement_speed": 1e39 }"#,
+        ];
+
+        for json in invalid {
+            let config: SnakeConfig = serde_json::from_str(json).unwrap();
+            assert!(config.validate().is_err(), "{json} was accepted");
+        }
+    }
+}
@@ -1,6 +0,0 @@
-pub const DISTANCE_BETWEEN_POINTS: f32 = 2.0;
-pub const INITIAL_LENGTH: u32 = 40;
-pub const SNAKE_THICKNESS: f32 = 3.0;
-
-pub const TURN_SPEED: f32 = 3.0;
-pub const MOVEMENT_SPEED: f32 = 20.0;
@@ -0,0 +1,213 @@
+use std::fs;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::player::{PlayerControls, MAX_PLAYERS, PLAYER_PRESETS};
+
+/// Local file the bindings are saved to, next to the game
+const BINDINGS_PATH: &str = "bindings.json";
+
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        // Loaded right away, every plugin reading keys can rely on it
+        app.insert_resource(ActionMap::load());
+    }
+}
+
+/// Everything a key can be bound to
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    TurnLeft(usize),
+    TurnRight(usize),
+    Boost(usize),
+    Pause,
+    SkipCountdown,
+    ToggleStepping,
+    StepContinue,
+    StepFrame,
+    StepRun,
+    ToggleSteppingUi,
+    ToggleFps,
+}
+
+impl Action {
+    pub fn label(&self) -> String {
+        match self {
+            Action::TurnLeft(player) => format!("{} LEFT", PLAYER_PRESETS[*player].name),
+            Action::TurnRight(player) => format!("{} RIGHT", PLAYER_PRESETS[*player].name),
+            Action::Boost(player) => format!("{} BOOST", PLAYER_PRESETS[*player].name),
+            Action::Pause => "PAUSE".into(),
+            Action::SkipCountdown => "SKIP COUNTDOWN".into(),
+            Action::ToggleStepping => "STEPPING".into(),
+            Action::StepContinue => "STEP CONTINUE".into(),
+            Action::StepFrame => "STEP FRAME".into(),
+            Action::StepRun => "STEP RUN".into(),
+            Action::ToggleSteppingUi => "STEPPING UI".into(),
+            Action::ToggleFps => "FPS".into(),
+        }
+    }
+}
+
+/// Keys of the system stepping debug tool
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct SteppingControls {
+    pub toggle: KeyCode,
+    /// Step the remainder of the frame
+    pub continue_frame: KeyCode,
+    pub step_frame: KeyCode,
+    /// Held to continue as fast as possible
+    pub run: KeyCode,
+    pub toggle_ui: KeyCode,
+}
+
+/// Tuning of the analog stick steering
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct GamepadControls {
+    /// Stick deflection ignored around the center, from 0 to 1
+    pub dead_zone: f32,
+    /// Turn applied at full deflection, relative to the turn speed
+    pub turn_rate: f32,
+}
+
+/// Key bound to every action, saved to a local file
+#[derive(Resource, Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionMap {
+    pub players: [PlayerControls; MAX_PLAYERS],
+    pub pause: KeyCode,
+    pub skip_countdown: KeyCode,
+    pub stepping: SteppingControls,
+    pub toggle_fps: KeyCode,
+    pub gamepad: GamepadControls,
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self {
+            players: PLAYER_PRESETS.map(|preset| preset.controls),
+            pause: KeyCode::Escape,
+            skip_countdown: KeyCode::Tab,
+            stepping: SteppingControls {
+                toggle: KeyCode::Backquote,
+                continue_frame: KeyCode::Space,
+                step_frame: KeyCode::KeyS,
+                run: KeyCode::Enter,
+                toggle_ui: KeyCode::F11,
+            },
+            toggle_fps: KeyCode::F12,
+            gamepad: GamepadControls {
+                dead_zone: 0.2,
+                turn_rate: 1.0,
+            },
+        }
+    }
+}
+
+impl ActionMap {
+    pub fn actions() -> impl Iterator<Item = Action> {
+        (0..MAX_PLAYERS)
+            .flat_map(|player| {
+                [
+                    Action::TurnLeft(player),
+                    Action::TurnRight(player),
+                    Action::Boost(player),
+                ]
+            })
+            .chain([
+                Action::Pause,
+                Action::SkipCountdown,
+                Action::ToggleStepping,
+                Action::StepContinue,
+                Action::StepFrame,
+                Action::StepRun,
+                Action::ToggleSteppingUi,
+                Action::ToggleFps,
+            ])
+    }
+
+    pub fn key(&self, action: Action) -> KeyCode {
+        match action {
+            Action::TurnLeft(player) => self.players[player].left,
+            Action::TurnRight(player) => self.players[player].right,
+            Action::Boost(player) => self.players[player].boost,
+            Action::Pause => self.pause,
+            Action::SkipCountdown => self.skip_countdown,
+            Action::ToggleStepping => self.stepping.toggle,
+            Action::StepContinue => self.stepping.continue_frame,
+            Action::StepFrame => self.stepping.step_frame,
+            Action::StepRun => self.stepping.run,
+            Action::ToggleSteppingUi => self.stepping.toggle_ui,
+            Action::ToggleFps => self.toggle_fps,
+        }
+    }
+
+    pub fn key_mut(&mut self, action: Action) -> &mut KeyCode {
+        match action {
+            Action::TurnLeft(player) => &mut self.players[player].left,
+            Action::TurnRight(player) => &mut self.players[player].right,
+            Action::Boost(player) => &mut self.players[player].boost,
+            Action::Pause => &mut self.pause,
+            Action::SkipCountdown => &mut self.skip_countdown,
+            Action::ToggleStepping => &mut self.stepping.toggle,
+            Action::StepContinue => &mut self.stepping.continue_frame,
+            Action::StepFrame => &mut self.stepping.step_frame,
+            Action::StepRun => &mut self.stepping.run,
+            Action::ToggleSteppingUi => &mut self.stepping.toggle_ui,
+            Action::ToggleFps => &mut self.toggle_fps,
+        }
+    }
+
+    /// Actions sharing their key with another action
+    pub fn conflicts(&self) -> Vec<(Action, Action)> {
+        let actions: Vec<Action> = Self::actions().collect();
+        let mut conflicts = Vec::new();
+
+        for (i, a) in actions.iter().enumerate() {
+            for b in &actions[i + 1..] {
+                if self.key(*a) == self.key(*b) {
+                    conflicts.push((*a, *b));
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    pub fn is_conflicting(&self, action: Action) -> bool {
+        Self::actions().any(|other| other != action && self.key(other) == self.key(action))
+    }
+
+    /// Read the bindings file, falling back to the default bindings when it
+    /// is missing or invalid
+    pub fn load() -> Self {
+        let Ok(content) = fs::read_to_string(BINDINGS_PATH) else {
+            return Self::default();
+        };
+
+        match serde_json::from_str::<Self>(&content) {
+            Ok(map) => {
+                for (a, b) in map.conflicts() {
+                    warn!("{} and {} are bound to the same key", a.label(), b.label());
+                }
+                map
+            }
+            Err(err) => {
+                warn!("Invalid bindings file {BINDINGS_PATH}, using defaults: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(std::io::Error::from)
+            .and_then(|content| fs::write(BINDINGS_PATH, content));
+
+        if let Err(err) = result {
+            error!("Failed to save bindings to {BINDINGS_PATH}: {err}");
+        }
+    }
+}
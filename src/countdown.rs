@@ -0,0 +1,127 @@
+use bevy::prelude::*;
+
+use crate::{app_state::GamePhase, config::SnakeConfig, controls::ActionMap};
+
+pub struct CountdownPlugin;
+
+impl Plugin for CountdownPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GamePhase::Countdown), setup_countdown)
+            .add_systems(OnExit(GamePhase::Countdown), cleanup_countdown)
+            .add_systems(
+                Update,
+                (
+                    update_countdown,
+                    // The press that started the game must not skip it as well
+                    skip_countdown.run_if(not(state_changed::<GamePhase>)),
+                )
+                    .chain()
+                    .run_if(in_state(GamePhase::Countdown)),
+            );
+    }
+}
+
+/// Time left before the snakes start moving
+#[derive(Resource)]
+struct Countdown(Timer);
+
+#[derive(Component)]
+struct CountdownUi;
+
+#[derive(Component)]
+struct CountdownText;
+
+fn setup_countdown(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    config: Res<SnakeConfig>,
+    action_map: Res<ActionMap>,
+) {
+    commands.insert_resource(Countdown(Timer::from_seconds(
+        config.countdown_duration,
+        TimerMode::Once,
+    )));
+
+    commands
+        .spawn((
+            CountdownUi,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+            parent.spawn((
+                CountdownText,
+                TextBundle::from_section(
+                    seconds_label(config.countdown_duration),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 120.0,
+                        color: Color::WHITE,
+                    },
+                ),
+            ));
+            parent.spawn(TextBundle::from_section(
+                format!("PRESS {:?} OR A TO SKIP", action_map.skip_countdown),
+                TextStyle {
+                    font,
+                    font_size: 18.0,
+                    color: Color::srgb(0.7, 0.7, 0.7),
+                },
+            ));
+        });
+}
+
+fn seconds_label(seconds: f32) -> String {
+    format!("{}", seconds.ceil().max(1.0))
+}
+
+fn update_countdown(
+    time: Res<Time>,
+    mut countdown: ResMut<Countdown>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+    mut query: Query<&mut Text, With<CountdownText>>,
+) {
+    countdown.0.tick(time.delta());
+    if countdown.0.finished() {
+        next_phase.set(GamePhase::Running);
+        return;
+    }
+
+    for mut text in &mut query {
+        text.sections[0].value = seconds_label(countdown.0.remaining_secs());
+    }
+}
+
+fn skip_countdown(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_map: Res<ActionMap>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    let gamepad_skip = gamepads.iter().any(|gamepad| {
+        gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::South))
+    });
+
+    if keyboard_input.just_pressed(action_map.skip_countdown) || gamepad_skip {
+        next_phase.set(GamePhase::Running);
+    }
+}
+
+fn cleanup_countdown(mut commands: Commands, query: Query<Entity, With<CountdownUi>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Countdown>();
+}
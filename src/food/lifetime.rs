@@ -0,0 +1,44 @@
+use bevy::prelude::*;
+
+use super::ReplaceFood;
+
+/// Seconds during which an expiring food fades out before disappearing
+const FADE_DURATION: f32 = 2.0;
+
+/// Food that only stays for a while before showing up elsewhere
+#[derive(Component, Debug)]
+pub struct Lifetime {
+    pub timer: Timer,
+}
+
+impl Lifetime {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+
+    /// Opacity of the food, lowered during the last seconds of its life
+    pub fn alpha(&self) -> f32 {
+        (self.timer.remaining_secs() / FADE_DURATION).clamp(0.0, 1.0)
+    }
+}
+
+/// Fade out the foods about to expire, then replace the expired ones
+pub fn expire_foods(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Lifetime, &mut Sprite)>,
+    mut replacements: EventWriter<ReplaceFood>,
+) {
+    for (entity, mut lifetime, mut sprite) in query.iter_mut() {
+        lifetime.timer.tick(time.delta());
+
+        if lifetime.timer.finished() {
+            commands.entity(entity).despawn();
+            replacements.send(ReplaceFood { food: entity });
+        } else {
+            sprite.color.set_alpha(lifetime.alpha());
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    app_state::AppState,
+    collision::collision_system,
+    components::{pending_growth::PendingGrowth, point_list::PointList, snake::Snake},
+    config::SnakeConfig,
+    free_space::FreeSpace,
+    game::GameSet,
+    grid,
+    levels::MovementMode,
+    rng::GameRng,
+    spatial::{self, SpatialIndex},
+};
+use lifetime::{expire_foods, Lifetime};
+use placement::FoodPlacement;
+use prey::{move_prey, Prey};
+use types::{FoodType, FoodTypes, FoodTypesPlugin};
+
+pub mod lifetime;
+pub mod placement;
+pub mod prey;
+pub mod types;
+
+pub struct FoodPlugin;
+
+impl Plugin for FoodPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(FoodTypesPlugin)
+            .add_event::<ReplaceFood>()
+            .add_event::<FoodEaten>()
+            // The level has to be in place and indexed to find free spots
+            .add_systems(
+                OnEnter(AppState::InGame),
+                spawn_food.after(spatial::setup_indexes),
+            )
+            .add_systems(OnExit(AppState::InGame), cleanup_foods)
+            .add_systems(
+                FixedUpdate,
+                (
+                    (move_prey, expire_foods).in_set(GameSet::Movement),
+                    (food_collision_system, replace_foods)
+                        .chain()
+                        .in_set(GameSet::Collision)
+                        .after(collision_system),
+                ),
+            );
+    }
+}
+
+/// A snake ate a food worth `score` points
+#[derive(Event, Debug)]
+pub struct FoodEaten {
+    pub snake: Entity,
+    pub score: u32,
+}
+
+/// A food of the level is gone, eaten or expired, another one has to show
+/// up somewhere else
+#[derive(Event, Debug)]
+pub struct ReplaceFood {
+    #[allow(dead_code)]
+    pub food: Entity,
+}
+
+#[derive(Component)]
+pub struct Food;
+
+/// Small food dropped by the snake, it does not respawn once eaten
+#[derive(Component)]
+pub struct Pellet;
+
+const INITIAL_FOOD_COUNT: u32 = 4;
+
+pub fn spawn_food(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    food_types: Res<FoodTypes>,
+    free_space: FreeSpace,
+    mut rng: ResMut<GameRng>,
+) {
+    spawn_food_count(
+        &mut commands,
+        &asset_server,
+        &food_types,
+        &mut FoodPlacement::new(&free_space),
+        &mut rng.0,
+        INITIAL_FOOD_COUNT,
+    );
+}
+
+/// Spawn foods of random types, picked according to their spawn weight, at
+/// free places of the arena
+fn spawn_food_count(
+    commands: &mut Commands,
+    asset_server: &Res<AssetServer>,
+    food_types: &FoodTypes,
+    placement: &mut FoodPlacement,
+    rng: &mut impl Rng,
+    count: u32,
+) {
+    for _ in 0..count {
+        let position = placement.sample(rng);
+        let food_type = food_types.pick(rng);
+        let mut food = commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load(&food_type.sprite),
+                transform: Transform {
+                    translation: position.extend(0.0),
+                    scale: Vec3::new(food_type.scale, food_type.scale, 1.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            Food,
+        ));
+
+        if let Some(seconds) = food_type.lifetime {
+            food.insert(Lifetime::new(seconds));
+        }
+        if food_type.prey.is_some() {
+            food.insert(Prey::new(rng));
+        }
+        food.insert(food_type);
+    }
+}
+
+/// Spawn a pellet at the given position
+pub fn spawn_pellet(commands: &mut Commands, asset_server: &Res<AssetServer>, position: Vec2) {
+    commands.spawn((
+        SpriteBundle {
+            texture: asset_server.load("images/foods/icon_0_0.png"),
+            transform: Transform {
+                translation: position.extend(0.0),
+                scale: Vec3::new(0.2, 0.2, 1.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        Food,
+        Pellet,
+    ));
+}
+
+type FoodSnakeQuery<'world, 'state, 'a> =
+    Query<'world, 'state, (Entity, &'a PointList, &'a mut PendingGrowth), With<Snake>>;
+
+#[allow(clippy::too_many_arguments)]
+pub fn food_collision_system(
+    mut commands: Commands,
+    food_query: Query<(Entity, &Transform, Option<&FoodType>), With<Food>>,
+    mut snake_query: FoodSnakeQuery,
+    food_index: Res<SpatialIndex<Food>>,
+    config: Res<SnakeConfig>,
+    movement_mode: Res<MovementMode>,
+    mut replacements: EventWriter<ReplaceFood>,
+    mut eaten_events: EventWriter<FoodEaten>,
+) {
+    let mut eaten_foods = Vec::new();
+
+    let cell_size = grid::cell_size(&config);
+    let search_radius = match *movement_mode {
+        MovementMode::Continuous => config.food_pickup_radius,
+        MovementMode::Grid => cell_size,
+    };
+
+    for (snake_entity, point_list, mut pending_growth) in snake_query.iter_mut() {
+        let head = point_list.0[0];
+
+        for item in food_index.query(head, search_radius) {
+            // The food can only be eaten once
+            if eaten_foods.contains(&item.entity) {
+                continue;
+            }
+            let Ok((food_entity, food_transform, food_type)) = food_query.get(item.entity) else {
+                continue;
+            };
+
+            let food_position = food_transform.translation.xy();
+            let eaten = match *movement_mode {
+                MovementMode::Continuous => {
+                    head.distance(food_position) < config.food_pickup_radius
+                }
+                MovementMode::Grid => {
+                    grid::snap(head, cell_size) == grid::snap(food_position, cell_size)
+                }
+            };
+            if !eaten {
+                continue;
+            }
+
+            commands.entity(food_entity).despawn();
+            eaten_foods.push(food_entity);
+
+            // Pellets have no type, only the foods of the level do
+            let Some(food_type) = food_type else {
+                pending_growth.add(config.pellet_growth_points);
+                eaten_events.send(FoodEaten {
+                    snake: snake_entity,
+                    score: config.pellet_score,
+                });
+                continue;
+            };
+
+            // The snake grows smoothly as it moves forward
+            pending_growth.add(food_type.growth);
+            eaten_events.send(FoodEaten {
+                snake: snake_entity,
+                score: food_type.score,
+            });
+
+            if let Some(effect) = food_type.effect {
+                effect.apply(&mut commands.entity(snake_entity));
+            }
+
+            replacements.send(ReplaceFood { food: food_entity });
+        }
+    }
+}
+
+/// Spawn new foods at free places for the ones eaten or expired this tick.
+/// The gone foods are despawned by now, and out of the food index.
+fn replace_foods(
+    mut commands: Commands,
+    mut replacements: EventReader<ReplaceFood>,
+    asset_server: Res<AssetServer>,
+    food_types: Res<FoodTypes>,
+    free_space: FreeSpace,
+    mut rng: ResMut<GameRng>,
+) {
+    let count = replacements.read().count();
+    if count == 0 {
+        return;
+    }
+
+    spawn_food_count(
+        &mut commands,
+        &asset_server,
+        &food_types,
+        &mut FoodPlacement::new(&free_space),
+        &mut rng.0,
+        count as u32,
+    );
+}
+
+pub fn cleanup_foods(mut commands: Commands, query: Query<Entity, With<Food>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn();
+    }
+}
@@ -0,0 +1,46 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::free_space::FreeSpace;
+
+/// Random positions compared to find the most open one
+const PLACEMENT_ATTEMPTS: usize = 32;
+
+/// Distance a food should keep from obstacles, snake bodies and other foods
+const FOOD_CLEARANCE: f32 = 20.0;
+
+/// Free places for the foods spawned on the same tick
+pub struct FoodPlacement<'a, 'w, 's> {
+    space: &'a FreeSpace<'w, 's>,
+    /// Places given to the previous foods, not in the index yet
+    taken: Vec<Vec2>,
+}
+
+impl<'a, 'w, 's> FoodPlacement<'a, 'w, 's> {
+    pub fn new(space: &'a FreeSpace<'w, 's>) -> Self {
+        Self {
+            space,
+            taken: Vec::new(),
+        }
+    }
+
+    /// Pick the most open of a few random places for a new food, and keep
+    /// it clear of the next ones
+    pub fn sample(&mut self, rng: &mut impl Rng) -> Vec2 {
+        let candidates = self.space.random_points(PLACEMENT_ATTEMPTS, rng);
+        let point = match self.space.rank(candidates, &[], &self.taken).first() {
+            Some(&(point, clearance)) => {
+                if clearance < FOOD_CLEARANCE {
+                    warn!("No free place found for a food, using one {clearance:.1} away from the closest obstacle, snake or food");
+                }
+                point
+            }
+            None => {
+                warn!("No place outside of the obstacles found for a food, using the center of the arena");
+                self.space.center()
+            }
+        };
+        self.taken.push(point);
+        point
+    }
+}
@@ -0,0 +1,118 @@
+use std::f32::consts::{PI, TAU};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+
+use super::types::FoodType;
+use crate::{
+    collision::point_in_polygon,
+    components::{obstacle::Obstacle, point_list::PointList, snake::Snake},
+    levels::arena::{Arena, BoundaryMode},
+    spatial::SpatialIndex,
+};
+
+/// Turn speed of a fleeing prey, in radians per second
+const FLEE_TURN_SPEED: f32 = 4.0;
+
+/// Largest random turn of a wandering prey, in radians per second
+const WANDER_TURN_SPEED: f32 = 3.0;
+
+/// Fraction of its top speed a prey moves at while wandering
+const WANDER_SPEED_FACTOR: f32 = 0.3;
+
+/// Distance ahead of a prey that has to be free of obstacles and walls
+const LOOKAHEAD: f32 = 10.0;
+
+/// Alternative headings tried on each side when the way is blocked
+const AVOID_STEPS: usize = 6;
+
+/// Food moving by itself, see [`PreyBehavior`](super::types::PreyBehavior)
+#[derive(Component, Debug)]
+pub struct Prey {
+    /// Direction the prey is moving toward, in radians
+    pub heading: f32,
+    /// Own randomness of the wandering, seeded from the game
+    rng: StdRng,
+}
+
+impl Prey {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Self {
+            heading: rng.gen_range(0.0..TAU),
+            rng: StdRng::seed_from_u64(rng.gen()),
+        }
+    }
+}
+
+/// Let the preys wander around, or run away from the closest head when it
+/// gets too close, going around obstacles and walls
+pub fn move_prey(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    obstacle_index: Res<SpatialIndex<Obstacle>>,
+    obstacle_query: Query<&PointList, (With<Obstacle>, Without<Snake>)>,
+    snake_query: Query<&PointList, (With<Snake>, Without<Obstacle>)>,
+    mut prey_query: Query<(&mut Transform, &mut Prey, &FoodType)>,
+) {
+    let dt = time.delta_seconds();
+    let heads: Vec<Vec2> = snake_query
+        .iter()
+        .map(|point_list| point_list.0[0])
+        .collect();
+
+    let is_free = |point: Vec2| {
+        if arena.boundary == BoundaryMode::Solid && !arena.contains(point) {
+            return false;
+        }
+        let point = arena.wrap(point);
+        !obstacle_index.query(point, 0.0).iter().any(|item| {
+            obstacle_query
+                .get(item.entity)
+                .is_ok_and(|polygon| point_in_polygon(&point, &polygon.0))
+        })
+    };
+
+    for (mut transform, mut prey, food_type) in prey_query.iter_mut() {
+        let Some(behavior) = food_type.prey else {
+            continue;
+        };
+        let position = transform.translation.xy();
+
+        let away = heads
+            .iter()
+            .map(|head| arena.delta(*head, position))
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        let speed = match away {
+            Some(away) if away.length() < behavior.flee_distance => {
+                let difference = (away.to_angle() - prey.heading + PI).rem_euclid(TAU) - PI;
+                let max_turn = FLEE_TURN_SPEED * dt;
+                prey.heading += difference.clamp(-max_turn, max_turn);
+                behavior.speed
+            }
+            _ => {
+                let turn = prey.rng.gen_range(-WANDER_TURN_SPEED..=WANDER_TURN_SPEED);
+                prey.heading += turn * dt;
+                behavior.speed * WANDER_SPEED_FACTOR
+            }
+        };
+
+        // Keep the wanted heading when it is free, otherwise the closest free
+        // one, alternating sides
+        let step = PI / AVOID_STEPS as f32;
+        let heading = (0..=AVOID_STEPS)
+            .flat_map(|i| {
+                [
+                    prey.heading + i as f32 * step,
+                    prey.heading - i as f32 * step,
+                ]
+            })
+            .find(|heading| is_free(position + Vec2::from_angle(*heading) * LOOKAHEAD));
+        let Some(heading) = heading else {
+            continue;
+        };
+        prey.heading = heading.rem_euclid(TAU);
+
+        let position = arena.wrap(position + Vec2::from_angle(prey.heading) * speed * dt);
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
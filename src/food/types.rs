@@ -0,0 +1,275 @@
+use bevy::{ecs::system::EntityCommands, prelude::*};
+use rand::{distributions::WeightedIndex, prelude::Distribution, Rng};
+use serde::Deserialize;
+
+use crate::{
+    collision::give_invincibility,
+    components::power_ups::{DoubleScore, Ghost, Magnet, PowerUp, Shield, Shrink, SlowMotion},
+    json_asset::{JsonAsset, JsonAssetHandle, JsonAssetPlugin},
+};
+
+pub struct FoodTypesPlugin;
+
+impl Plugin for FoodTypesPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(JsonAssetPlugin::<FoodTypes>::default())
+            .init_resource::<FoodTypes>()
+            .add_systems(Update, apply_food_types);
+    }
+}
+
+/// How often a food type shows up, on top of its spawn weight
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+impl Rarity {
+    fn factor(&self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Uncommon => 0.5,
+            Rarity::Rare => 0.2,
+            Rarity::Legendary => 0.05,
+        }
+    }
+}
+
+/// What happens to the snake eating a food with an effect
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EffectKind {
+    /// The snake can't be hit
+    Invincible,
+    /// Gives [`Ghost`], going through obstacles
+    Ghost,
+    /// Gives [`SlowMotion`], slowing down every other snake
+    SlowMotion,
+    /// Gives [`Shrink`], a thinner body
+    Shrink,
+    /// Gives [`Magnet`], pulling the foods around the head
+    Magnet,
+    /// Gives [`DoubleScore`], doubling the points of the foods eaten
+    DoubleScore,
+    /// Gives [`Shield`], absorbing the next hit
+    Shield,
+}
+
+/// Something special happening to the snake eating the food, for this many
+/// seconds
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct FoodEffect {
+    #[serde(rename = "type")]
+    pub kind: EffectKind,
+    pub duration: f32,
+}
+
+impl FoodEffect {
+    pub fn apply(&self, snake: &mut EntityCommands) {
+        let duration = self.duration;
+        match self.kind {
+            EffectKind::Invincible => give_invincibility(snake, duration),
+            EffectKind::Ghost => Ghost::grant(snake, duration),
+            EffectKind::SlowMotion => SlowMotion::grant(snake, duration),
+            EffectKind::Shrink => Shrink::grant(snake, duration),
+            EffectKind::Magnet => Magnet::grant(snake, duration),
+            EffectKind::DoubleScore => DoubleScore::grant(snake, duration),
+            EffectKind::Shield => Shield::grant(snake, duration),
+        }
+    }
+}
+
+/// Food running away from the snakes instead of waiting to be eaten
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct PreyBehavior {
+    /// Top speed, in units per second
+    pub speed: f32,
+    /// Distance under which the food flees the closest head, it wanders
+    /// slowly otherwise
+    pub flee_distance: f32,
+}
+
+/// A kind of food, also attached to every food of that kind in the arena
+#[derive(Component, Deserialize, Clone, Debug)]
+pub struct FoodType {
+    #[allow(dead_code)]
+    pub name: String,
+    /// Image path, relative to the assets folder
+    pub sprite: String,
+    #[serde(default = "default_scale")]
+    pub scale: f32,
+    /// Points added to the snake eating it
+    pub growth: usize,
+    /// Points scored by the player eating it, before the bonuses
+    pub score: u32,
+    /// Relative chance to be picked when a food spawns
+    pub weight: f32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    #[serde(default)]
+    pub effect: Option<FoodEffect>,
+    /// Seconds before the food fades out and shows up elsewhere, it stays
+    /// until eaten when missing
+    #[serde(default)]
+    pub lifetime: Option<f32>,
+    #[serde(default)]
+    pub prey: Option<PreyBehavior>,
+}
+
+fn default_scale() -> f32 {
+    0.5
+}
+
+impl Default for FoodType {
+    fn default() -> Self {
+        Self {
+            name: "taco".into(),
+            sprite: "images/foods/icon_0_0.png".into(),
+            scale: default_scale(),
+            growth: 3,
+            score: 10,
+            weight: 1.0,
+            rarity: Rarity::Common,
+            effect: None,
+            lifetime: None,
+            prey: None,
+        }
+    }
+}
+
+impl FoodType {
+    /// Chance to be picked, relative to the other types
+    pub fn spawn_weight(&self) -> f32 {
+        (self.weight * self.rarity.factor()).max(0.0)
+    }
+}
+
+/// Every food that can spawn in the arena.
+///
+/// Loaded from `assets/food.types.json` and reloaded whenever the file
+/// changes on disk, a single plain food is used until the file is loaded.
+#[derive(Asset, Resource, TypePath, Deserialize, Clone, Debug)]
+pub struct FoodTypes {
+    pub types: Vec<FoodType>,
+}
+
+impl Default for FoodTypes {
+    fn default() -> Self {
+        Self {
+            types: vec![FoodType::default()],
+        }
+    }
+}
+
+impl FoodTypes {
+    /// Chances of the types to be picked, `None` when no type can spawn
+    fn distribution(&self) -> Option<WeightedIndex<f32>> {
+        WeightedIndex::new(self.types.iter().map(FoodType::spawn_weight)).ok()
+    }
+
+    /// Weighted random type, the plain food when no type can spawn
+    pub fn pick(&self, rng: &mut impl Rng) -> FoodType {
+        self.distribution()
+            .and_then(|index| self.types.get(index.sample(rng)))
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+impl JsonAsset for FoodTypes {
+    const PATH: &'static str = "food.types.json";
+    const EXTENSION: &'static str = "types.json";
+
+    /// Reject the values the game can't work with, like negative durations
+    /// that would make the timers panic
+    fn validate(&self) -> Result<(), String> {
+        for food_type in &self.types {
+            let mut values = vec![("weight", food_type.weight), ("scale", food_type.scale)];
+            values.extend(food_type.lifetime.map(|lifetime| ("lifetime", lifetime)));
+            values.extend(
+                food_type
+                    .effect
+                    .map(|effect| ("effect duration", effect.duration)),
+            );
+            if let Some(prey) = food_type.prey {
+                values.push(("prey speed", prey.speed));
+                values.push(("prey flee distance", prey.flee_distance));
+            }
+
+            if let Some((field, value)) = values
+                .into_iter()
+                .find(|(_, value)| !value.is_finite() || *value < 0.0)
+            {
+                return Err(format!(
+                    "invalid {field} {value} for the {} food type",
+                    food_type.name
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy the loaded asset into the [`FoodTypes`] resource every time the file
+/// is (re)loaded. Foods already in the arena keep their old type.
+fn apply_food_types(
+    mut events: EventReader<AssetEvent<FoodTypes>>,
+    handle: Res<JsonAssetHandle<FoodTypes>>,
+    assets: Res<Assets<FoodTypes>>,
+    mut food_types: ResMut<FoodTypes>,
+) {
+    let Some(loaded) = handle.reloaded(&mut events, &assets) else {
+        return;
+    };
+
+    if loaded.distribution().is_none() {
+        warn!(
+            "No food type can spawn, check the weights in {}",
+            FoodTypes::PATH
+        );
+    }
+    *food_types = loaded.clone();
+    info!("{} food types loaded", food_types.types.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shipped_types_are_valid() {
+        let food_types: FoodTypes =
+            serde_json::from_str(include_str!("../../assets/food.types.json")).unwrap();
+        assert_eq!(food_types.validate(), Ok(()));
+    }
+
+    #[test]
+    fn negative_or_infinite_numbers_are_rejected() {
+        let invalid = [
+            r#"{ "weight": -1.0 }"#,
+            r#"{ "weight": 1.0, "scale": -0.5 }"#,
+            r#"{ "weight": 1.0, "lifetime": -3.0 }"#,
+            r#"{ "weight": 1.0, "effect": { "type": "ghost", "duration": -1.0 } }"#,
+            r#"{ "weight": 1.0, "prey": { "speed": 1e39, "flee_distance": 60.0 } }"#,
+            r#"{ "weight": 1.0, "prey": { "speed": 10.0, "flee_distance": -60.0 } }"#,
+        ];
+
+        for fields in invalid {
+            let json = format!(
+                r#"{{ "types": [{}] }}"#,
+                fields.replacen(
+                    '{',
+                    r#"{ "name": "bad", "sprite": "bad.png", "growth": 1, "score": 1,"#,
+                    1
+                )
+            );
+            let food_types: FoodTypes = serde_json::from_str(&json).unwrap();
+            assert!(food_types.validate().is_err(), "{fields} was accepted");
+        }
+    }
+}
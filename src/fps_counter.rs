@@ -2,6 +2,8 @@ use bevy::diagnostic::DiagnosticsStore;
 use bevy::diagnostic::FrameTimeDiagnosticsPlugin;
 use bevy::prelude::*;
 
+use crate::controls::ActionMap;
+
 pub struct FpsCounterPlugin;
 
 impl Plugin for FpsCounterPlugin {
@@ -127,12 +129,13 @@ fn fps_text_update_system(
     }
 }
 
-/// Toggle the FPS counter when pressing F12
+/// Toggle the FPS counter when pressing its key, F12 by default
 fn fps_counter_showhide(
     mut q: Query<&mut Visibility, With<FpsRoot>>,
     kbd: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
 ) {
-    if kbd.just_pressed(KeyCode::F12) {
+    if kbd.just_pressed(action_map.toggle_fps) {
         let mut vis = q.single_mut();
         *vis = match *vis {
             Visibility::Hidden => Visibility::Visible,
@@ -0,0 +1,153 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+use rand::Rng;
+
+use crate::{
+    collision::{distance_to_segment, point_in_polygon},
+    components::{obstacle::Obstacle, point_list::PointList, snake::Snake},
+    config::SnakeConfig,
+    food::Food,
+    grid,
+    levels::{
+        arena::{Arena, BoundaryMode},
+        MovementMode,
+    },
+    spatial::SpatialIndex,
+};
+
+/// Distance up to which the surroundings of a point are looked at, anything
+/// further away counts as this far
+const CLEARANCE_RANGE: f32 = 64.0;
+
+type ObstacleShapeQuery<'world, 'state> =
+    Query<'world, 'state, &'static PointList, (With<Obstacle>, Without<Snake>)>;
+
+type SnakeBodyQuery<'world, 'state> =
+    Query<'world, 'state, &'static PointList, (With<Snake>, Without<Obstacle>)>;
+
+/// Open space around the points of the arena, to find free places for new
+/// snakes and foods.
+///
+/// Only the obstacles, snakes and foods close to a point are looked at,
+/// through the spatial indexes.
+#[derive(SystemParam)]
+pub struct FreeSpace<'w, 's> {
+    arena: Res<'w, Arena>,
+    config: Res<'w, SnakeConfig>,
+    movement_mode: Res<'w, MovementMode>,
+    obstacle_index: Res<'w, SpatialIndex<Obstacle>>,
+    snake_index: Res<'w, SpatialIndex<Snake>>,
+    food_index: Res<'w, SpatialIndex<Food>>,
+    obstacle_query: ObstacleShapeQuery<'w, 's>,
+    snake_query: SnakeBodyQuery<'w, 's>,
+    food_query: Query<'w, 's, &'static Transform, With<Food>>,
+}
+
+impl FreeSpace<'_, '_> {
+    pub fn arena(&self) -> &Arena {
+        &self.arena
+    }
+
+    /// Distance from the point to the closest obstacle, solid wall, snake or
+    /// food, capped to [`CLEARANCE_RANGE`], zero when the point itself is not
+    /// reachable.
+    ///
+    /// The bodies of the `ignored` snakes don't count, the `taken` points do:
+    /// they are the places handed out on this tick, not indexed yet.
+    pub fn clearance(&self, point: Vec2, ignored: &[Entity], taken: &[Vec2]) -> f32 {
+        let mut clearance = CLEARANCE_RANGE;
+
+        if self.arena.boundary == BoundaryMode::Solid {
+            if !self.arena.contains(point) {
+                return 0.0;
+            }
+            let to_min = point - self.arena.min;
+            let to_max = self.arena.max - point;
+            clearance = clearance.min(to_min.min(to_max).min_element());
+        }
+
+        for item in self.obstacle_index.query(point, CLEARANCE_RANGE) {
+            let Ok(polygon) = self.obstacle_query.get(item.entity) else {
+                continue;
+            };
+            if point_in_polygon(&point, &polygon.0) {
+                return 0.0;
+            }
+            for (i, a) in polygon.0.iter().enumerate() {
+                let b = polygon.0[(i + 1) % polygon.0.len()];
+                clearance = clearance.min(distance_to_segment(&point, a, &b));
+            }
+        }
+
+        for item in self.snake_index.query(point, CLEARANCE_RANGE) {
+            if ignored.contains(&item.entity) {
+                continue;
+            }
+            let Ok(point_list) = self.snake_query.get(item.entity) else {
+                continue;
+            };
+            let (Some(a), Some(b)) = (point_list.0.get(item.part), point_list.0.get(item.part + 1))
+            else {
+                continue;
+            };
+            // Measure on the side of the seam where the segment is
+            let b = *a + self.arena.delta(*a, *b);
+            let point = *a + self.arena.delta(*a, point);
+            clearance = clearance.min(distance_to_segment(&point, a, &b));
+        }
+
+        for item in self.food_index.query(point, CLEARANCE_RANGE) {
+            if let Ok(transform) = self.food_query.get(item.entity) {
+                let food = transform.translation.xy();
+                clearance = clearance.min(self.arena.delta(point, food).length());
+            }
+        }
+
+        for other in taken {
+            clearance = clearance.min(self.arena.delta(point, *other).length());
+        }
+
+        clearance
+    }
+
+    /// The points with their clearance, from the most open to the least,
+    /// the ones that are not reachable left out
+    pub fn rank(
+        &self,
+        points: impl IntoIterator<Item = Vec2>,
+        ignored: &[Entity],
+        taken: &[Vec2],
+    ) -> Vec<(Vec2, f32)> {
+        let mut ranked: Vec<(Vec2, f32)> = points
+            .into_iter()
+            .map(|point| (point, self.clearance(point, ignored, taken)))
+            .filter(|(_, clearance)| *clearance > 0.0)
+            .collect();
+        ranked.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+        ranked
+    }
+
+    /// Random points of the arena, on cell centers in the grid mode
+    pub fn random_points(&self, count: usize, rng: &mut impl Rng) -> Vec<Vec2> {
+        (0..count)
+            .map(|_| {
+                self.snap(Vec2::new(
+                    rng.gen_range(self.arena.min.x..self.arena.max.x),
+                    rng.gen_range(self.arena.min.y..self.arena.max.y),
+                ))
+            })
+            .collect()
+    }
+
+    /// Center of the arena, the last resort when no free place is found
+    pub fn center(&self) -> Vec2 {
+        self.snap((self.arena.min + self.arena.max) / 2.0)
+    }
+
+    /// Everything sits on cell centers in the grid mode
+    pub fn snap(&self, point: Vec2) -> Vec2 {
+        match *self.movement_mode {
+            MovementMode::Continuous => point,
+            MovementMode::Grid => grid::snap(point, grid::cell_size(&self.config)),
+        }
+    }
+}
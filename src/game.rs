@@ -1,39 +1,117 @@
+use std::f32::consts::TAU;
+
 use bevy::{prelude::*, render::mesh::Indices, sprite::Mesh2dHandle};
 
-use crate::app_state::AppState;
+use crate::app_state::{AppState, GamePhase};
+use crate::boost::Boost;
+use crate::bots::{strategies, Bot};
 use crate::components::{
-    direction::Direction, obstacle::Obstacle, point_list::PointList, snake::Snake,
+    direction::Direction,
+    obstacle::Obstacle,
+    path_history::PathHistory,
+    pending_growth::PendingGrowth,
+    player::{Player, PLAYER_PRESETS},
+    point_list::PointList,
+    power_ups::{Shrink, SlowMotion},
+    snake::Snake,
+    steering_intent::SteeringIntent,
 };
-use crate::{
-    consts::{DISTANCE_BETWEEN_POINTS, MOVEMENT_SPEED, TURN_SPEED},
-    load_level,
+use crate::free_space::FreeSpace;
+use crate::input::KeyboardControls;
+use crate::levels::{
+    arena::{Arena, ArenaBorder},
+    MovementMode,
 };
+use crate::pointer::PointerControls;
+use crate::power_ups;
+use crate::respawn::{SpawnArea, SpawnPoints};
+use crate::rng::GameRng;
+use crate::spatial;
+use crate::{config::SnakeConfig, grid, load_level, settings::GameSettings};
 
 pub struct GamePlugin;
 
+/// Ordering of the gameplay pipeline, which runs in `FixedUpdate` so that a
+/// given sequence of inputs always produces the same simulation. Nothing in
+/// it runs while the game is paused.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GameSet {
+    /// Input sources running in the fixed steps, such as bots, fill the
+    /// steering intents
+    Input,
+    /// The steering intents are applied
+    Steering,
+    Movement,
+    Collision,
+    Rules,
+}
+
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
-        let snake_system_set = (update_direction, update_position, update_mesh);
-        app.add_systems(OnEnter(AppState::InGame), setup)
-            .add_systems(OnExit(AppState::InGame), (cleanup_snake, cleanup_obstacles))
-            .add_systems(Update, snake_system_set.run_if(in_state(AppState::InGame)));
+        app.configure_sets(
+            FixedUpdate,
+            (
+                GameSet::Input,
+                GameSet::Steering,
+                GameSet::Movement,
+                GameSet::Collision,
+                GameSet::Rules,
+            )
+                .chain()
+                .run_if(in_state(GamePhase::Running)),
+        )
+        .add_systems(
+            OnEnter(AppState::InGame),
+            (setup, spawn_snakes.after(spatial::setup_indexes)),
+        )
+        .add_systems(
+            OnExit(AppState::InGame),
+            (cleanup_snake, cleanup_obstacles, cleanup_level),
+        )
+        .add_systems(
+            FixedUpdate,
+            (
+                update_direction
+                    .in_set(GameSet::Steering)
+                    .run_if(resource_exists_and_equals(MovementMode::Continuous)),
+                (
+                    move_head.run_if(resource_exists_and_equals(MovementMode::Continuous)),
+                    follow_head,
+                )
+                    .chain()
+                    .in_set(GameSet::Movement),
+            ),
+        )
+        .add_systems(Update, update_mesh.run_if(in_state(AppState::InGame)));
     }
 }
 
-fn setup(
+pub fn setup(
     mut commands: Commands,
     // asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<SnakeConfig>,
 ) {
-    commands.spawn(Snake::get_default_entity_components(
-        &mut meshes,
-        &mut materials,
-    ));
-
     let simple_level: crate::levels::Level = load_level!("simple");
+    let arena = simple_level.arena;
+    let movement_mode = simple_level.movement;
+
+    for border in arena.create_border_components() {
+        commands.spawn(border);
+    }
+    commands.insert_resource(arena);
+    commands.insert_resource(movement_mode);
+    commands.insert_resource(SpawnPoints(simple_level.spawn_points));
+
+    for mut obstacle in simple_level.obstacles {
+        if movement_mode == MovementMode::Grid {
+            let cell_size = grid::cell_size(&config);
+            for point in obstacle.0.iter_mut() {
+                *point = grid::snap(*point, cell_size);
+            }
+        }
 
-    for obstacle in simple_level.obstacles {
         commands.spawn(Obstacle::create_from_point_list(
             obstacle,
             &mut materials,
@@ -43,71 +121,141 @@ fn setup(
 }
 
 fn update_mesh(
-    mut query: Query<(&Snake, &PointList, &mut Mesh2dHandle)>,
+    mut query: Query<(&Snake, &PointList, &mut Mesh2dHandle, Has<Shrink>)>,
     mut meshes: ResMut<Assets<Mesh>>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
 ) {
-    let Ok((_, point_list, mesh_handle)) = query.get_single_mut() else {
-        return;
-    };
-
-    let Some(mesh) = meshes.get_mut(mesh_handle.0.id()) else {
-        return;
-    };
+    for (_, point_list, mesh_handle, shrunk) in query.iter_mut() {
+        let Some(mesh) = meshes.get_mut(mesh_handle.0.id()) else {
+            continue;
+        };
 
-    let (indices, vertices) = Snake::get_indices_and_vertices(point_list);
+        let thickness = power_ups::thickness(shrunk, &config);
+        let (indices, vertices) = Snake::get_indices_and_vertices(point_list, thickness, &arena);
 
-    mesh.insert_indices(Indices::U32(indices));
-    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+        mesh.insert_indices(Indices::U32(indices));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, vertices);
+    }
 }
 
 fn update_direction(
     time: Res<Time>,
-    keyboard_input: Res<ButtonInput<KeyCode>>,
-    mut query: Query<&mut Direction, With<Snake>>,
+    config: Res<SnakeConfig>,
+    mut query: Query<(&mut Direction, &SteeringIntent), With<Snake>>,
 ) {
-    let mut direction = query.single_mut();
-
-    let mut offset = None;
-    if keyboard_input.pressed(KeyCode::ArrowLeft) {
-        offset = Some(TURN_SPEED);
-    } else if keyboard_input.pressed(KeyCode::ArrowRight) {
-        offset = Some(-TURN_SPEED);
+    for (mut direction, intent) in query.iter_mut() {
+        turn(
+            &mut direction,
+            intent.turn,
+            config.turn_speed,
+            time.delta_seconds(),
+        );
     }
+}
 
-    if let Some(offset) = offset {
-        direction.0 += offset * time.delta_seconds();
-        if direction.0 < 0.0 {
-            direction.0 += std::f32::consts::TAU;
-        } else if direction.0 > std::f32::consts::TAU {
-            direction.0 -= std::f32::consts::TAU;
-        }
-    }
+/// Rotate the snake by `amount` of its turn speed, from -1 (full right) to
+/// 1 (full left)
+fn turn(direction: &mut Direction, amount: f32, turn_speed: f32, dt: f32) {
+    direction.0 = (direction.0 + amount.clamp(-1.0, 1.0) * turn_speed * dt).rem_euclid(TAU);
 }
 
-/// We want to have a snake effect
-fn update_position(time: Res<Time>, mut query: Query<(&mut PointList, &Direction), With<Snake>>) {
+/// Move the head forward in the continuous movement mode
+fn move_head(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    mut query: Query<(Entity, &PointList, &mut PathHistory, &Direction, &Boost), With<Snake>>,
+    slow_motion_query: Query<Entity, With<SlowMotion>>,
+) {
     let dt = time.delta_seconds();
 
-    let Ok((mut point_list, direction)) = query.get_single_mut() else {
-        return;
-    };
+    for (entity, point_list, mut path_history, direction, boost) in query.iter_mut() {
+        // Move the head of the snake, faster while boosting and slower during
+        // the slow motion of another snake
+        let speed = boost.speed(&config) * power_ups::speed_factor(entity, &slow_motion_query);
+        let head_movement = Vec2::new(direction.0.cos(), direction.0.sin()) * speed * dt;
+        path_history.record(arena.wrap(point_list.0[0] + head_movement));
+    }
+}
+
+/// Make the body follow the path taken by the head
+pub fn follow_head(
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    mut query: Query<(&mut PointList, &mut PathHistory, &mut PendingGrowth), With<Snake>>,
+) {
+    let spacing = config.distance_between_points;
 
-    // Move the head of the snake
-    let head_movement = Vec2::new(direction.0.cos(), direction.0.sin()) * MOVEMENT_SPEED * dt;
-    point_list.0[0] += head_movement;
+    for (mut point_list, mut path_history, mut pending_growth) in query.iter_mut() {
+        // While growing, the path is not trimmed: a point is added every time
+        // the head moved far enough away from the tail
+        while pending_growth.0 > 0
+            && path_history.length(&arena) >= point_list.0.len() as f32 * spacing
+        {
+            let tail = *point_list
+                .0
+                .last()
+                .expect("Snake should have at least 1 point");
+            point_list.push(tail);
+            pending_growth.0 -= 1;
+        }
 
-    // Update the positions of the other points
-    for i in 1..point_list.0.len() {
-        let prev_point = point_list.0[i - 1];
-        let current_point = &mut point_list.0[i];
+        // Place the points along the path of the head
+        path_history.sample(&mut point_list.0, spacing, &arena);
+
+        if pending_growth.0 > 0 {
+            // The tail holds still at the end of the path
+            if let (Some(tail), Some(end)) = (point_list.0.last_mut(), path_history.end()) {
+                *tail = end;
+            }
+        } else {
+            path_history.trim((point_list.0.len() - 1) as f32 * spacing, &arena);
+        }
+    }
+}
+
+/// Place the snakes of the players at the spawn points of the level, or at
+/// other safe places when there are not enough of them
+#[allow(clippy::too_many_arguments)]
+pub fn spawn_snakes(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    config: Res<SnakeConfig>,
+    settings: Res<GameSettings>,
+    movement_mode: Res<MovementMode>,
+    spawn_points: Res<SpawnPoints>,
+    free_space: FreeSpace,
+    mut rng: ResMut<GameRng>,
+) {
+    let mut area = SpawnArea::new(&free_space, Vec::new(), &config);
 
-        // Calculate the distance and direction to the previous point
-        let distance_to_prev = current_point.distance(prev_point);
-        let dir_to_prev = (prev_point - *current_point).normalize_or_zero();
+    for (i, preset) in PLAYER_PRESETS
+        .iter()
+        .enumerate()
+        .take(settings.total_players())
+    {
+        let (head, direction) = area.place(&spawn_points.0, *movement_mode, &mut rng.0);
+        let mut snake = commands.spawn((
+            Snake::get_entity_components(
+                head,
+                direction,
+                Color::from(preset.color),
+                &mut meshes,
+                &mut materials,
+                free_space.arena(),
+                &config,
+            ),
+            Player(i),
+        ));
 
-        // Move the current point towards the previous point if it's too far
-        if distance_to_prev > DISTANCE_BETWEEN_POINTS {
-            *current_point += dir_to_prev * MOVEMENT_SPEED * dt;
+        if settings.is_bot(i) {
+            snake.insert(Bot(strategies::for_bot(i - settings.player_count)));
+        } else if settings.uses_pointer(i) {
+            snake.insert(PointerControls);
+        } else {
+            snake.insert(KeyboardControls);
         }
     }
 }
@@ -123,3 +271,12 @@ fn cleanup_obstacles(mut commands: Commands, query: Query<Entity, With<Obstacle>
         commands.entity(entity).despawn_recursive();
     }
 }
+
+fn cleanup_level(mut commands: Commands, query: Query<Entity, With<ArenaBorder>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<Arena>();
+    commands.remove_resource::<MovementMode>();
+    commands.remove_resource::<SpawnPoints>();
+}
@@ -0,0 +1,293 @@
+use bevy::{input::InputSystem, prelude::*, ui::UiSystem};
+
+use crate::{
+    app_state::AppState,
+    bots::Bot,
+    components::{player::Player, steering_intent::SteeringIntent},
+    controls::{ActionMap, GamepadControls},
+    grid::Turn,
+    input::keyboard_steering,
+    pointer::pointer_steering,
+};
+
+pub struct GamepadPlugin;
+
+impl Plugin for GamepadPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MenuFocus>().add_systems(
+            PreUpdate,
+            (
+                gamepad_steering
+                    .after(InputSystem)
+                    .after(keyboard_steering)
+                    .after(pointer_steering)
+                    .run_if(in_state(AppState::InGame)),
+                gamepad_menu_navigation.after(UiSystem::Focus),
+            ),
+        );
+    }
+}
+
+/// Button pressed when going back from a menu with the gamepad
+#[derive(Component)]
+pub struct MenuBack;
+
+/// Button of the current menu selected with the gamepad
+#[derive(Resource, Default)]
+struct MenuFocus {
+    index: Option<usize>,
+    /// Button pressed last frame, released on the next one
+    pressed: Option<Entity>,
+}
+
+/// Connected gamepads, the n-th one steers the snake of the n-th player
+fn sorted_gamepads(gamepads: &Gamepads) -> Vec<Gamepad> {
+    let mut gamepads: Vec<Gamepad> = gamepads.iter().collect();
+    gamepads.sort_by_key(|gamepad| gamepad.id);
+    gamepads
+}
+
+/// Stick deflection past the dead-zone, rescaled from 0 to 1
+fn apply_dead_zone(value: f32, settings: &GamepadControls) -> f32 {
+    let dead_zone = settings.dead_zone.clamp(0.0, 0.99);
+    if value.abs() < dead_zone {
+        return 0.0;
+    }
+    value.signum() * (value.abs() - dead_zone) / (1.0 - dead_zone)
+}
+
+/// Steer with the left stick or the D-pad, boost with the shoulder buttons.
+/// Runs after the keyboard and the mouse, and only takes over while used.
+fn gamepad_steering(
+    gamepads: Res<Gamepads>,
+    buttons: Res<ButtonInput<GamepadButton>>,
+    axes: Res<Axis<GamepadAxis>>,
+    action_map: Res<ActionMap>,
+    mut query: Query<(&mut SteeringIntent, &Player), Without<Bot>>,
+) {
+    let gamepads = sorted_gamepads(&gamepads);
+
+    for (mut intent, player) in query.iter_mut() {
+        let Some(&gamepad) = gamepads.get(player.0) else {
+            continue;
+        };
+        let button = |button_type| GamepadButton::new(gamepad, button_type);
+
+        let stick = axes
+            .get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))
+            .unwrap_or(0.0);
+        let stick_turn =
+            -apply_dead_zone(stick, &action_map.gamepad) * action_map.gamepad.turn_rate;
+
+        let turn = if buttons.pressed(button(GamepadButtonType::DPadLeft)) {
+            1.0
+        } else if buttons.pressed(button(GamepadButtonType::DPadRight)) {
+            -1.0
+        } else {
+            stick_turn.clamp(-1.0, 1.0)
+        };
+        if turn != 0.0 {
+            intent.turn = turn;
+        }
+
+        intent.boost |= buttons.any_pressed([
+            button(GamepadButtonType::LeftTrigger),
+            button(GamepadButtonType::RightTrigger),
+        ]);
+
+        if buttons.just_pressed(button(GamepadButtonType::DPadLeft)) {
+            intent.request_turn(Turn::Left);
+        }
+        if buttons.just_pressed(button(GamepadButtonType::DPadRight)) {
+            intent.request_turn(Turn::Right);
+        }
+    }
+}
+
+/// Move between the buttons of the menu with the D-pad of any gamepad, press
+/// the selected one with A and go back with B. The buttons go through their
+/// `Interaction`, like with the mouse.
+fn gamepad_menu_navigation(
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    mut focus: ResMut<MenuFocus>,
+    mut button_query: Query<(
+        Entity,
+        &mut Interaction,
+        &GlobalTransform,
+        &ViewVisibility,
+        Has<MenuBack>,
+    )>,
+) {
+    // Release the button pressed last frame
+    if let Some(entity) = focus.pressed.take() {
+        if let Ok((_, mut interaction, ..)) = button_query.get_mut(entity) {
+            *interaction = Interaction::None;
+        }
+    }
+
+    // Only the buttons of the menu on top, the ones below are hidden
+    let mut buttons: Vec<(Entity, Vec2, bool)> = button_query
+        .iter()
+        .filter(|(_, _, _, visibility, _)| visibility.get())
+        .map(|(entity, _, transform, _, is_back)| (entity, transform.translation().xy(), is_back))
+        .collect();
+    if buttons.is_empty() {
+        focus.index = None;
+        return;
+    }
+    // Top to bottom, then left to right
+    buttons.sort_by(|(_, a, _), (_, b, _)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+
+    let pressed = |button_type| {
+        gamepads
+            .iter()
+            .any(|gamepad| gamepad_buttons.just_pressed(GamepadButton::new(gamepad, button_type)))
+    };
+
+    let count = buttons.len();
+    let previous = focus.index.map(|index| index.min(count - 1));
+    let mut index = previous;
+    if pressed(GamepadButtonType::DPadDown) || pressed(GamepadButtonType::DPadRight) {
+        index = Some(index.map_or(0, |index| (index + 1) % count));
+    }
+    if pressed(GamepadButtonType::DPadUp) || pressed(GamepadButtonType::DPadLeft) {
+        index = Some(index.map_or(0, |index| (index + count - 1) % count));
+    }
+    focus.index = index;
+
+    // The selected button is highlighted like when hovered by the mouse
+    if let Some(previous) = previous.filter(|previous| Some(*previous) != index) {
+        if let Ok((_, mut interaction, ..)) = button_query.get_mut(buttons[previous].0) {
+            interaction.set_if_neq(Interaction::None);
+        }
+    }
+    let focused = index.map(|index| buttons[index].0);
+    if let Some(Ok((_, mut interaction, ..))) = focused.map(|entity| button_query.get_mut(entity)) {
+        interaction.set_if_neq(Interaction::Hovered);
+    }
+
+    let target = if pressed(GamepadButtonType::East) {
+        buttons
+            .iter()
+            .find(|(_, _, is_back)| *is_back)
+            .map(|(entity, _, _)| *entity)
+    } else if pressed(GamepadButtonType::South) {
+        focused
+    } else {
+        None
+    };
+    if let Some(Ok((entity, mut interaction, ..))) =
+        target.map(|entity| button_query.get_mut(entity))
+    {
+        *interaction = Interaction::Pressed;
+        focus.pressed = Some(entity);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::{
+        gamepad::{GamepadConnection, GamepadConnectionEvent, GamepadInfo},
+        InputPlugin,
+    };
+
+    use super::*;
+
+    fn controls(dead_zone: f32) -> GamepadControls {
+        GamepadControls {
+            dead_zone,
+            turn_rate: 1.0,
+        }
+    }
+
+    #[test]
+    fn dead_zone_is_ignored_and_rescaled() {
+        let controls = controls(0.2);
+
+        assert_eq!(apply_dead_zone(0.0, &controls), 0.0);
+        assert_eq!(apply_dead_zone(0.15, &controls), 0.0);
+        assert_eq!(apply_dead_zone(-0.15, &controls), 0.0);
+        assert_eq!(apply_dead_zone(1.0, &controls), 1.0);
+        assert_eq!(apply_dead_zone(-1.0, &controls), -1.0);
+        assert!((apply_dead_zone(0.6, &controls) - 0.5).abs() < 1e-6);
+        assert!((apply_dead_zone(-0.6, &controls) + 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn dead_zone_out_of_range_is_clamped() {
+        let whole_stick = controls(1.5);
+        assert_eq!(apply_dead_zone(0.5, &whole_stick), 0.0);
+        assert_eq!(apply_dead_zone(1.0, &whole_stick), 1.0);
+        assert!(apply_dead_zone(-1.0, &whole_stick).is_finite());
+
+        let negative = controls(-0.5);
+        assert_eq!(apply_dead_zone(0.5, &negative), 0.5);
+    }
+
+    /// App with one gamepad connected and the snakes of two players
+    fn app() -> (App, Gamepad, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin))
+            .init_resource::<ActionMap>()
+            .add_systems(Update, gamepad_steering);
+
+        let gamepad = Gamepad::new(0);
+        app.world_mut().send_event(GamepadConnectionEvent::new(
+            gamepad,
+            GamepadConnection::Connected(GamepadInfo {
+                name: "Test gamepad".into(),
+            }),
+        ));
+        let first = app
+            .world_mut()
+            .spawn((SteeringIntent::default(), Player(0)))
+            .id();
+        let second = app
+            .world_mut()
+            .spawn((SteeringIntent::default(), Player(1)))
+            .id();
+        app.update();
+
+        (app, gamepad, first, second)
+    }
+
+    #[test]
+    fn stick_and_triggers_steer_the_first_player() {
+        let (mut app, gamepad, first, second) = app();
+
+        app.world_mut()
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX), -1.0);
+        app.world_mut()
+            .resource_mut::<ButtonInput<GamepadButton>>()
+            .press(GamepadButton::new(gamepad, GamepadButtonType::RightTrigger));
+        app.update();
+
+        let intent = app.world().get::<SteeringIntent>(first).unwrap();
+        assert_eq!(intent.turn, 1.0);
+        assert!(intent.boost);
+
+        // No second gamepad, the second player is left alone
+        let intent = app.world().get::<SteeringIntent>(second).unwrap();
+        assert_eq!(intent.turn, 0.0);
+        assert!(!intent.boost);
+    }
+
+    #[test]
+    fn d_pad_overrides_the_stick() {
+        let (mut app, gamepad, first, _) = app();
+
+        app.world_mut()
+            .resource_mut::<Axis<GamepadAxis>>()
+            .set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX), -1.0);
+        app.world_mut()
+            .resource_mut::<ButtonInput<GamepadButton>>()
+            .press(GamepadButton::new(gamepad, GamepadButtonType::DPadRight));
+        app.update();
+
+        let intent = app.world().get::<SteeringIntent>(first).unwrap();
+        assert_eq!(intent.turn, -1.0);
+        assert!(!intent.boost);
+    }
+}
@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::f32::consts::{FRAC_PI_2, TAU};
+
+use bevy::prelude::*;
+
+use crate::{
+    boost::Boost,
+    components::{
+        direction::Direction, path_history::PathHistory, point_list::PointList,
+        power_ups::SlowMotion, snake::Snake, steering_intent::SteeringIntent,
+    },
+    config::SnakeConfig,
+    food::Food,
+    game::{follow_head, GameSet},
+    levels::{arena::Arena, MovementMode},
+    power_ups,
+};
+
+/// Size of a grid cell, relative to the snake thickness
+const CELL_SIZE_FACTOR: f32 = 2.0;
+
+/// Number of turns remembered ahead of the next step, so that quick
+/// double-turns are not lost between two steps
+const MAX_QUEUED_TURNS: usize = 3;
+
+pub struct GridPlugin;
+
+impl Plugin for GridPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                (snap_food_to_grid, queue_turns).in_set(GameSet::Steering),
+                step_grid.in_set(GameSet::Movement).before(follow_head),
+            )
+                .run_if(resource_exists_and_equals(MovementMode::Grid)),
+        );
+    }
+}
+
+pub fn cell_size(config: &SnakeConfig) -> f32 {
+    config.snake_thickness * CELL_SIZE_FACTOR
+}
+
+/// Move a point to the center of its grid cell
+pub fn snap(point: Vec2, cell_size: f32) -> Vec2 {
+    (point / cell_size).round() * cell_size
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Turn {
+    fn angle(self) -> f32 {
+        match self {
+            Turn::Left => FRAC_PI_2,
+            Turn::Right => -FRAC_PI_2,
+        }
+    }
+}
+
+/// State of a snake moving on the grid
+#[derive(Component, Default)]
+pub struct GridMovement {
+    queued_turns: VecDeque<Turn>,
+    /// Fraction of a cell travelled since the last step
+    progress: f32,
+}
+
+impl GridMovement {
+    pub fn has_queued_turns(&self) -> bool {
+        !self.queued_turns.is_empty()
+    }
+
+    pub fn queue_turn(&mut self, turn: Turn) {
+        if self.queued_turns.len() < MAX_QUEUED_TURNS {
+            self.queued_turns.push_back(turn);
+        }
+    }
+}
+
+/// Move the turns requested since the last tick to the queue of the snake
+fn queue_turns(mut query: Query<(&mut GridMovement, &mut SteeringIntent), With<Snake>>) {
+    for (mut grid_movement, mut intent) in query.iter_mut() {
+        for turn in intent.take_turn_requests() {
+            grid_movement.queue_turn(turn);
+        }
+    }
+}
+
+type GridSnakeQuery<'world, 'state, 'a> = Query<
+    'world,
+    'state,
+    (
+        Entity,
+        &'a PointList,
+        &'a mut PathHistory,
+        &'a mut Direction,
+        &'a mut GridMovement,
+        &'a Boost,
+    ),
+    With<Snake>,
+>;
+
+/// Move the head one cell at a time, applying one queued turn per step
+fn step_grid(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    mut query: GridSnakeQuery,
+    slow_motion_query: Query<Entity, With<SlowMotion>>,
+) {
+    let cell_size = cell_size(&config);
+
+    for (entity, point_list, mut path_history, mut direction, mut grid_movement, boost) in
+        query.iter_mut()
+    {
+        let speed = boost.speed(&config) * power_ups::speed_factor(entity, &slow_motion_query);
+        grid_movement.progress += speed * time.delta_seconds() / cell_size;
+
+        let mut head = snap(point_list.0[0], cell_size);
+        while grid_movement.progress >= 1.0 {
+            grid_movement.progress -= 1.0;
+
+            let mut angle = (direction.0 / FRAC_PI_2).round() * FRAC_PI_2;
+            if let Some(turn) = grid_movement.queued_turns.pop_front() {
+                angle += turn.angle();
+            }
+            direction.0 = angle.rem_euclid(TAU);
+
+            let heading = Vec2::from_angle(direction.0).round();
+            head = arena.wrap(head + heading * cell_size);
+            path_history.record(head);
+        }
+    }
+}
+
+fn snap_food_to_grid(config: Res<SnakeConfig>, mut query: Query<&mut Transform, Added<Food>>) {
+    let cell_size = cell_size(&config);
+
+    for mut transform in query.iter_mut() {
+        let position = snap(transform.translation.xy(), cell_size);
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
@@ -0,0 +1,131 @@
+use bevy::{input::InputSystem, prelude::*};
+
+use crate::{
+    app_state::AppState,
+    components::{player::Player, steering_intent::SteeringIntent},
+    controls::ActionMap,
+    grid::Turn,
+};
+
+pub struct KeyboardPlugin;
+
+impl Plugin for KeyboardPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            keyboard_steering
+                .after(InputSystem)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Snake steered with the keys bound to its player
+#[derive(Component, Clone, Copy, Debug)]
+pub struct KeyboardControls;
+
+/// Fill the steering intent of keyboard players. Runs every frame, before the
+/// fixed steps, so that short key presses are not missed.
+pub fn keyboard_steering(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
+    mut query: Query<(&mut SteeringIntent, &Player), With<KeyboardControls>>,
+) {
+    for (mut intent, player) in query.iter_mut() {
+        let controls = &action_map.players[player.0];
+        intent.turn = if keyboard_input.pressed(controls.left) {
+            1.0
+        } else if keyboard_input.pressed(controls.right) {
+            -1.0
+        } else {
+            0.0
+        };
+        intent.boost = keyboard_input.pressed(controls.boost);
+
+        if keyboard_input.just_pressed(controls.left) {
+            intent.request_turn(Turn::Left);
+        }
+        if keyboard_input.just_pressed(controls.right) {
+            intent.request_turn(Turn::Right);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::input::{
+        keyboard::{Key, KeyboardInput, NativeKey},
+        ButtonState, InputPlugin,
+    };
+
+    use super::*;
+    use crate::components::player::PlayerControls;
+
+    /// App with the snakes of two keyboard players
+    fn app() -> (App, Entity, Entity) {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, InputPlugin))
+            .init_resource::<ActionMap>()
+            .add_systems(Update, keyboard_steering);
+
+        let first = app
+            .world_mut()
+            .spawn((SteeringIntent::default(), Player(0), KeyboardControls))
+            .id();
+        let second = app
+            .world_mut()
+            .spawn((SteeringIntent::default(), Player(1), KeyboardControls))
+            .id();
+        app.update();
+
+        (app, first, second)
+    }
+
+    /// Press a key of the player like the window would, so that it is seen as
+    /// just pressed on the next update
+    fn press(app: &mut App, player: usize, key: impl Fn(&PlayerControls) -> KeyCode) {
+        let key_code = key(&app.world().resource::<ActionMap>().players[player]);
+        app.world_mut().send_event(KeyboardInput {
+            key_code,
+            logical_key: Key::Unidentified(NativeKey::Unidentified),
+            state: ButtonState::Pressed,
+            window: Entity::PLACEHOLDER,
+        });
+    }
+
+    #[test]
+    fn keys_steer_their_own_player() {
+        let (mut app, first, second) = app();
+
+        press(&mut app, 0, |controls| controls.left);
+        press(&mut app, 0, |controls| controls.boost);
+        app.update();
+
+        let mut intent = app.world_mut().get_mut::<SteeringIntent>(first).unwrap();
+        assert_eq!(intent.turn, 1.0);
+        assert!(intent.boost);
+        let turns: Vec<Turn> = intent.take_turn_requests().collect();
+        assert!(matches!(turns[..], [Turn::Left]));
+
+        let intent = app.world().get::<SteeringIntent>(second).unwrap();
+        assert_eq!(intent.turn, 0.0);
+        assert!(!intent.boost);
+        assert!(!intent.has_turn_requests());
+    }
+
+    #[test]
+    fn held_key_requests_a_single_turn() {
+        let (mut app, first, _) = app();
+
+        press(&mut app, 0, |controls| controls.right);
+        app.update();
+        app.update();
+        app.update();
+
+        let mut intent = app.world_mut().get_mut::<SteeringIntent>(first).unwrap();
+        assert_eq!(intent.turn, -1.0);
+        assert!(!intent.boost);
+        let turns: Vec<Turn> = intent.take_turn_requests().collect();
+        assert!(matches!(turns[..], [Turn::Right]));
+    }
+}
@@ -0,0 +1,93 @@
+use std::marker::PhantomData;
+
+use bevy::asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bevy::prelude::*;
+use serde::de::{DeserializeOwned, Error as _};
+
+/// Game data read from a JSON file of the assets folder, and reloaded
+/// whenever the file changes on disk
+pub trait JsonAsset: Asset + DeserializeOwned {
+    /// Path of the file in the assets folder
+    const PATH: &'static str;
+    /// Extension the loader is registered for, like `config.json`
+    const EXTENSION: &'static str;
+
+    /// Reject the values the game can't work with, the file is not applied
+    /// then
+    fn validate(&self) -> Result<(), String>;
+}
+
+/// Register the loader of `T` and load its file at startup
+pub struct JsonAssetPlugin<T>(PhantomData<fn() -> T>);
+
+impl<T> Default for JsonAssetPlugin<T> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T: JsonAsset> Plugin for JsonAssetPlugin<T> {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<T>()
+            .register_asset_loader(JsonAssetLoader::<T>(PhantomData))
+            .add_systems(Startup, load_json_asset::<T>);
+    }
+}
+
+struct JsonAssetLoader<T>(PhantomData<fn() -> T>);
+
+impl<T: JsonAsset> AssetLoader for JsonAssetLoader<T> {
+    type Asset = T;
+    type Settings = ();
+    type Error = serde_json::Error;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<T, Self::Error> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(serde_json::Error::io)?;
+        let asset: T = serde_json::from_slice(&bytes)?;
+        asset.validate().map_err(serde_json::Error::custom)?;
+        Ok(asset)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        std::slice::from_ref(&T::EXTENSION)
+    }
+}
+
+/// Keep the handle alive so the asset is not unloaded and keeps being watched
+#[derive(Resource)]
+pub struct JsonAssetHandle<T: Asset>(Handle<T>);
+
+impl<T: JsonAsset> JsonAssetHandle<T> {
+    /// The asset, when its file was (re)loaded since the last call
+    pub fn reloaded<'a>(
+        &self,
+        events: &mut EventReader<AssetEvent<T>>,
+        assets: &'a Assets<T>,
+    ) -> Option<&'a T> {
+        let reloaded = events.read().any(|event| {
+            matches!(
+                event,
+                AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }
+                    if *id == self.0.id()
+            )
+        });
+        if reloaded {
+            assets.get(&self.0)
+        } else {
+            None
+        }
+    }
+}
+
+fn load_json_asset<T: JsonAsset>(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(JsonAssetHandle::<T>(asset_server.load(T::PATH)));
+}
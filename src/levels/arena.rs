@@ -0,0 +1,107 @@
+use bevy::color::palettes::tailwind;
+use bevy::prelude::*;
+use serde::Deserialize;
+
+const BORDER_THICKNESS: f32 = 2.0;
+
+/// What happens when the snake reaches the edge of the arena
+#[derive(Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BoundaryMode {
+    /// Leaving the arena counts as a collision
+    #[default]
+    Solid,
+    /// Leaving the arena teleports the snake to the opposite edge
+    Wrap,
+}
+
+/// Marker for the sprites outlining the arena
+#[derive(Component)]
+pub struct ArenaBorder;
+
+/// Playable rectangle of the current level
+#[derive(Resource, Deserialize, Clone, Copy, Debug)]
+pub struct Arena {
+    pub min: Vec2,
+    pub max: Vec2,
+    #[serde(default)]
+    pub boundary: BoundaryMode,
+}
+
+impl Arena {
+    pub fn size(&self) -> Vec2 {
+        self.max - self.min
+    }
+
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Bring a point that left the arena back from the opposite edge.
+    /// Points are left untouched when the boundary is solid.
+    pub fn wrap(&self, point: Vec2) -> Vec2 {
+        match self.boundary {
+            BoundaryMode::Solid => point,
+            BoundaryMode::Wrap => self.min + (point - self.min).rem_euclid(self.size()),
+        }
+    }
+
+    /// Shortest displacement going from `from` to `to`, crossing the seam
+    /// when the boundary wraps around.
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let delta = to - from;
+        match self.boundary {
+            BoundaryMode::Solid => delta,
+            BoundaryMode::Wrap => {
+                let size = self.size();
+                delta - size * (delta / size).round()
+            }
+        }
+    }
+
+    /// Create the four sprites drawn just outside of the arena edges
+    pub fn create_border_components(&self) -> Vec<(ArenaBorder, SpriteBundle)> {
+        let color = match self.boundary {
+            BoundaryMode::Solid => Color::from(tailwind::RED_500),
+            BoundaryMode::Wrap => Color::from(tailwind::GRAY_600),
+        };
+        let center = (self.min + self.max) / 2.0;
+        let size = self.size() + BORDER_THICKNESS * 2.0;
+        let half_gap = (self.size() + BORDER_THICKNESS) / 2.0;
+
+        [
+            (
+                Vec2::new(0.0, half_gap.y),
+                Vec2::new(size.x, BORDER_THICKNESS),
+            ),
+            (
+                Vec2::new(0.0, -half_gap.y),
+                Vec2::new(size.x, BORDER_THICKNESS),
+            ),
+            (
+                Vec2::new(half_gap.x, 0.0),
+                Vec2::new(BORDER_THICKNESS, size.y),
+            ),
+            (
+                Vec2::new(-half_gap.x, 0.0),
+                Vec2::new(BORDER_THICKNESS, size.y),
+            ),
+        ]
+        .into_iter()
+        .map(|(offset, size)| {
+            (
+                ArenaBorder,
+                SpriteBundle {
+                    sprite: Sprite {
+                        color,
+                        custom_size: Some(size),
+                        ..Default::default()
+                    },
+                    transform: Transform::from_translation((center + offset).extend(0.0)),
+                    ..Default::default()
+                },
+            )
+        })
+        .collect()
+    }
+}
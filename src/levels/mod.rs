@@ -1,14 +1,35 @@
 use std::fs::read_to_string;
 
 use crate::components::point_list::PointList;
+use arena::Arena;
+use bevy::prelude::*;
 use serde::Deserialize;
 use serde_json::from_str;
 
+pub mod arena;
+
 #[derive(Deserialize)]
 pub struct Level {
     #[allow(dead_code)]
     pub name: String,
+    pub arena: Arena,
+    #[serde(default)]
+    pub movement: MovementMode,
     pub obstacles: Vec<PointList>,
+    /// Preferred places to respawn, used when they are safe
+    #[serde(default)]
+    pub spawn_points: Vec<Vec2>,
+}
+
+/// How the snake moves in the level
+#[derive(Resource, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MovementMode {
+    /// Free-angle steering
+    #[default]
+    Continuous,
+    /// Classic snake: the head moves cell by cell and only turns by 90°
+    Grid,
 }
 
 #[allow(dead_code)]
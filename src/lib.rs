@@ -0,0 +1,30 @@
+pub mod app_state;
+pub mod boost;
+pub mod bots;
+pub mod collision;
+pub mod components;
+pub mod config;
+pub mod controls;
+pub mod countdown;
+pub mod food;
+pub mod fps_counter;
+pub mod free_space;
+pub mod game;
+pub mod gamepad;
+pub mod grid;
+pub mod input;
+pub mod json_asset;
+pub mod levels;
+pub mod pause;
+pub mod players_lifes;
+pub mod pointer;
+pub mod power_ups;
+pub mod respawn;
+pub mod rng;
+pub mod score;
+pub mod settings;
+pub mod spatial;
+pub mod stepping;
+pub mod ui;
+pub mod ui_bindings;
+pub mod ui_game_over;
@@ -1,42 +1,47 @@
-use app_state::AppStatePlugin;
 use bevy::prelude::*;
-use collision::CollisionPlugin;
-use food::FoodPlugin;
-use fps_counter::FpsCounterPlugin;
-use game::GamePlugin;
-use players_lifes::PlayersLifesPlugin;
-use stepping::SteppingEguiPlugin;
-use ui::UIPlugin;
-use ui_game_over::UIGameOverPlugin;
-
-mod app_state;
-mod collision;
-mod components;
-mod consts;
-mod food;
-mod fps_counter;
-mod game;
-mod levels;
-mod players_lifes;
-mod stepping;
-mod ui;
-mod ui_game_over;
+use snake::{
+    app_state::AppStatePlugin, boost::BoostPlugin, bots::BotsPlugin, collision::CollisionPlugin,
+    config::SnakeConfigPlugin, controls::ControlsPlugin, countdown::CountdownPlugin,
+    food::FoodPlugin, fps_counter::FpsCounterPlugin, game::GamePlugin, gamepad::GamepadPlugin,
+    grid::GridPlugin, input::KeyboardPlugin, pause::PausePlugin, players_lifes::PlayersLifesPlugin,
+    pointer::PointerPlugin, power_ups::PowerUpsPlugin, respawn::RespawnPlugin, rng::GameRngPlugin,
+    score::ScorePlugin, settings::SettingsPlugin, spatial::SpatialPlugin,
+    stepping::SteppingEguiPlugin, ui::UIPlugin, ui_bindings::UIBindingsPlugin,
+    ui_game_over::UIGameOverPlugin,
+};
 
 fn main() {
     App::new()
         .add_plugins(DefaultPlugins)
+        .add_plugins(ControlsPlugin)
         .add_plugins(
             SteppingEguiPlugin::default()
                 .add_schedule(Update)
                 .add_schedule(FixedUpdate),
         )
         .add_plugins(FpsCounterPlugin)
+        .add_plugins(SnakeConfigPlugin)
         .add_plugins(AppStatePlugin)
+        .add_plugins(SettingsPlugin)
         .add_plugins(PlayersLifesPlugin)
+        .add_plugins(KeyboardPlugin)
+        .add_plugins(PointerPlugin)
+        .add_plugins(GamepadPlugin)
         .add_plugins(GamePlugin)
+        .add_plugins(BoostPlugin)
+        .add_plugins(GridPlugin)
+        .add_plugins(BotsPlugin)
         .add_plugins(CollisionPlugin)
+        .add_plugins(RespawnPlugin)
+        .add_plugins(GameRngPlugin)
+        .add_plugins(SpatialPlugin)
         .add_plugins(UIPlugin)
         .add_plugins(UIGameOverPlugin)
+        .add_plugins(PausePlugin)
+        .add_plugins(CountdownPlugin)
+        .add_plugins(UIBindingsPlugin)
         .add_plugins(FoodPlugin)
+        .add_plugins(PowerUpsPlugin)
+        .add_plugins(ScorePlugin)
         .run();
 }
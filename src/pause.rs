@@ -0,0 +1,205 @@
+use bevy::{prelude::*, window::WindowFocused};
+
+use crate::{
+    app_state::{AppState, GamePhase},
+    controls::ActionMap,
+    gamepad::MenuBack,
+    ui::{spawn_button, update_button_border, BtnInteractionQuery, Menu},
+    ui_bindings::BindingsScreen,
+};
+
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(PausedFrom(GamePhase::Running))
+            .add_systems(OnEnter(GamePhase::Paused), create_ui)
+            .add_systems(OnExit(GamePhase::Paused), cleanup_ui)
+            .add_systems(
+                Update,
+                (
+                    (toggle_pause, pause_on_focus_lost)
+                        .run_if(in_state(BindingsScreen::Closed))
+                        .run_if(in_state(AppState::InGame)),
+                    (
+                        resume_button_interaction_system,
+                        restart_button_interaction_system,
+                        settings_button_interaction_system,
+                        quit_button_interaction_system,
+                    )
+                        .run_if(in_state(GamePhase::Paused)),
+                ),
+            );
+    }
+}
+
+/// Phase the game was in when it got paused, the one resuming goes back to
+#[derive(Resource)]
+struct PausedFrom(GamePhase);
+
+#[derive(Component)]
+struct PauseUi;
+
+#[derive(Component)]
+struct ResumeButton;
+
+#[derive(Component)]
+struct RestartButton;
+
+#[derive(Component)]
+struct SettingsButton;
+
+#[derive(Component)]
+struct QuitButton;
+
+/// Pause or resume with the pause key or the start button of a gamepad
+fn toggle_pause(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_buttons: Res<ButtonInput<GamepadButton>>,
+    action_map: Res<ActionMap>,
+    phase: Res<State<GamePhase>>,
+    mut paused_from: ResMut<PausedFrom>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    let start_pressed = gamepads.iter().any(|gamepad| {
+        gamepad_buttons.just_pressed(GamepadButton::new(gamepad, GamepadButtonType::Start))
+    });
+
+    if keyboard_input.just_pressed(action_map.pause) || start_pressed {
+        match phase.get() {
+            GamePhase::Running => {
+                paused_from.0 = GamePhase::Running;
+                next_phase.set(GamePhase::Paused);
+            }
+            GamePhase::Paused => next_phase.set(paused_from.0),
+            // Too short to be worth pausing
+            GamePhase::Countdown => {}
+        }
+    }
+}
+
+/// Pause when the window loses the focus, also during the countdown so that
+/// the snakes don't start moving while nobody is looking. Resuming from the
+/// countdown starts it over.
+fn pause_on_focus_lost(
+    mut focus_events: EventReader<WindowFocused>,
+    phase: Res<State<GamePhase>>,
+    mut paused_from: ResMut<PausedFrom>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    let focus_lost = focus_events.read().any(|event| !event.focused);
+    if !focus_lost {
+        return;
+    }
+
+    match phase.get() {
+        GamePhase::Running | GamePhase::Countdown => {
+            paused_from.0 = *phase.get();
+            next_phase.set(GamePhase::Paused);
+        }
+        GamePhase::Paused => {}
+    }
+}
+
+/// Darken the frozen game and show the pause menu over it
+fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            PauseUi,
+            Menu,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(0.6).into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+            parent.spawn(
+                TextBundle::from_section(
+                    "PAUSED",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 50.0,
+                        color: Color::WHITE,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..default()
+                }),
+            );
+
+            spawn_button(
+                parent,
+                (ResumeButton, MenuBack),
+                (),
+                "RESUME".into(),
+                300.0,
+                &font,
+            );
+            spawn_button(parent, RestartButton, (), "RESTART".into(), 300.0, &font);
+            spawn_button(parent, SettingsButton, (), "SETTINGS".into(), 300.0, &font);
+            spawn_button(parent, QuitButton, (), "QUIT TO MENU".into(), 300.0, &font);
+        });
+}
+
+fn resume_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<ResumeButton>,
+    paused_from: Res<PausedFrom>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            next_phase.set(paused_from.0);
+        }
+    }
+}
+
+fn restart_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<RestartButton>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            next_state.set(AppState::Restart);
+        }
+    }
+}
+
+fn settings_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<SettingsButton>,
+    mut next_screen: ResMut<NextState<BindingsScreen>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            next_screen.set(BindingsScreen::Open);
+        }
+    }
+}
+
+fn quit_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<QuitButton>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            next_state.set(AppState::MainMenu);
+        }
+    }
+}
+
+fn cleanup_ui(mut commands: Commands, query: Query<Entity, With<PauseUi>>) {
+    for entity in &mut query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
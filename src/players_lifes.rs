@@ -1,6 +1,13 @@
 use bevy::prelude::*;
 
-use crate::app_state::AppState;
+use crate::{
+    app_state::AppState,
+    components::player::PLAYER_PRESETS,
+    config::SnakeConfig,
+    game::GameSet,
+    score::PlayersScores,
+    settings::{GameMode, GameSettings},
+};
 
 #[derive(Component)]
 pub struct PlayerLifeUiRoot;
@@ -8,15 +15,24 @@ pub struct PlayerLifeUiRoot;
 #[derive(Component)]
 pub struct PlayerLifeUiText;
 
+/// Remaining lifes of each player, indexed by [`Player`](crate::components::player::Player)
 #[derive(Resource)]
-pub struct PlayersLifes(pub u32);
+pub struct PlayersLifes(pub Vec<u32>);
 
 impl PlayersLifes {
-    pub fn new() -> Self {
-        Self(1)
+    pub fn new(player_count: usize, lifes: u32) -> Self {
+        Self(vec![lifes; player_count])
+    }
+
+    pub fn alive_count(&self) -> usize {
+        self.0.iter().filter(|lifes| **lifes > 0).count()
     }
 }
 
+/// Snakes killed by each player, indexed by [`Player`](crate::components::player::Player)
+#[derive(Resource)]
+pub struct PlayersKills(pub Vec<u32>);
+
 pub struct PlayersLifesPlugin;
 
 impl Plugin for PlayersLifesPlugin {
@@ -25,12 +41,17 @@ impl Plugin for PlayersLifesPlugin {
             .add_systems(OnExit(AppState::InGame), cleanup_players_lifes_ui)
             .add_systems(
                 Update,
-                (update_players_lifes_ui, game_over).run_if(in_state(AppState::InGame)),
-            );
+                update_players_lifes_ui.run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(FixedUpdate, game_over.in_set(GameSet::Rules));
     }
 }
 
-pub fn setup_players_lifes(mut commands: Commands) {
+pub fn setup_players_lifes(
+    mut commands: Commands,
+    settings: Res<GameSettings>,
+    config: Res<SnakeConfig>,
+) {
     let root = commands
         .spawn((
             PlayerLifeUiRoot,
@@ -47,28 +68,31 @@ pub fn setup_players_lifes(mut commands: Commands) {
         ))
         .id();
 
+    // One section per player, after the label
+    let mut sections = vec![TextSection {
+        value: "LIFES:".into(),
+        style: TextStyle {
+            font_size: 16.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    }];
+    for preset in PLAYER_PRESETS.iter().take(settings.total_players()) {
+        sections.push(TextSection {
+            value: " N/A".into(),
+            style: TextStyle {
+                font_size: 16.0,
+                color: Color::from(preset.color),
+                ..default()
+            },
+        });
+    }
+
     let text = commands
         .spawn((
             PlayerLifeUiText,
             TextBundle {
-                text: Text::from_sections([
-                    TextSection {
-                        value: "LIFES: ".into(),
-                        style: TextStyle {
-                            font_size: 16.0,
-                            color: Color::WHITE,
-                            ..default()
-                        },
-                    },
-                    TextSection {
-                        value: " N/A".into(),
-                        style: TextStyle {
-                            font_size: 16.0,
-                            color: Color::WHITE,
-                            ..default()
-                        },
-                    },
-                ]),
+                text: Text::from_sections(sections),
                 ..Default::default()
             },
         ))
@@ -76,20 +100,58 @@ pub fn setup_players_lifes(mut commands: Commands) {
 
     commands.entity(root).push_children(&[text]);
 
-    commands.insert_resource(PlayersLifes::new());
+    commands.insert_resource(PlayersLifes::new(
+        settings.total_players(),
+        config.starting_lifes.max(1),
+    ));
+    commands.insert_resource(PlayersKills(vec![0; settings.total_players()]));
+    commands.insert_resource(PlayersScores::new(settings.total_players()));
 }
 
 pub fn update_players_lifes_ui(
     players_lifes: Res<PlayersLifes>,
+    players_kills: Res<PlayersKills>,
+    players_scores: Res<PlayersScores>,
+    settings: Res<GameSettings>,
     mut query: Query<&mut Text, With<PlayerLifeUiText>>,
 ) {
     for mut text in &mut query {
-        text.sections[1].value = format!(" {:>2.0}", players_lifes.0);
+        for (i, lifes) in players_lifes.0.iter().enumerate() {
+            let name = if settings.is_bot(i) {
+                "BOT"
+            } else {
+                PLAYER_PRESETS[i].name
+            };
+            let score = players_scores.0[i];
+            let mut value = format!(" {} {:>2.0} {} PTS", name, lifes, score.points);
+            if score.combo > 1.0 {
+                value += &format!(" x{:.1}", score.combo);
+            }
+            if players_kills.0[i] > 0 {
+                value += &format!(" ({} KO)", players_kills.0[i]);
+            }
+            text.sections[i + 1].value = value;
+        }
     }
 }
 
-pub fn game_over(mut next_state: ResMut<NextState<AppState>>, players_lifes: Res<PlayersLifes>) {
-    if players_lifes.0 == 0 {
+/// The round ends once every human player is dead, and in versus as soon as
+/// a single snake remains
+pub fn game_over(
+    mut next_state: ResMut<NextState<AppState>>,
+    players_lifes: Res<PlayersLifes>,
+    settings: Res<GameSettings>,
+) {
+    let humans_alive = players_lifes.0[..settings.player_count]
+        .iter()
+        .filter(|lifes| **lifes > 0)
+        .count();
+    let last_one_standing = settings.mode == GameMode::Versus
+        && players_lifes.0.len() > 1
+        && players_lifes.alive_count() <= 1;
+    let is_over = humans_alive == 0 || last_one_standing;
+
+    if is_over {
         next_state.set(AppState::GameOver);
     }
 }
@@ -0,0 +1,91 @@
+use std::f32::consts::{FRAC_PI_4, PI, TAU};
+
+use bevy::{input::InputSystem, prelude::*, window::PrimaryWindow};
+
+use crate::{
+    app_state::AppState,
+    components::{
+        direction::Direction, point_list::PointList, snake::Snake, steering_intent::SteeringIntent,
+    },
+    config::SnakeConfig,
+    grid::{GridMovement, Turn},
+    levels::{arena::Arena, MovementMode},
+    ui::MainCamera,
+};
+
+pub struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            pointer_steering
+                .after(InputSystem)
+                .run_if(in_state(AppState::InGame)),
+        );
+    }
+}
+
+/// Snake steered towards the mouse cursor, boosting while the left button is
+/// held
+#[derive(Component, Clone, Copy, Debug)]
+pub struct PointerControls;
+
+type PointerQuery<'world, 'state, 'a, 'b, 'c, 'd> = Query<
+    'world,
+    'state,
+    (
+        &'a mut SteeringIntent,
+        &'b Direction,
+        &'c PointList,
+        &'d GridMovement,
+    ),
+    (With<Snake>, With<PointerControls>),
+>;
+
+/// Turn towards the world position of the cursor. The turn is limited by the
+/// turn speed when the intent is applied, like any other input.
+pub fn pointer_steering(
+    mouse_input: Res<ButtonInput<MouseButton>>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    movement_mode: Res<MovementMode>,
+    window_query: Query<&Window, With<PrimaryWindow>>,
+    camera_query: Query<(&Camera, &GlobalTransform), With<MainCamera>>,
+    mut query: PointerQuery,
+) {
+    let cursor = window_query
+        .get_single()
+        .ok()
+        .and_then(|window| window.cursor_position())
+        .zip(camera_query.get_single().ok())
+        .and_then(|(cursor, (camera, camera_transform))| {
+            camera.viewport_to_world_2d(camera_transform, cursor)
+        });
+
+    for (mut intent, direction, point_list, grid_movement) in query.iter_mut() {
+        intent.boost = mouse_input.pressed(MouseButton::Left);
+
+        let Some(cursor) = cursor else {
+            intent.turn = 0.0;
+            continue;
+        };
+
+        let target = arena.delta(point_list.0[0], cursor).to_angle();
+        let difference = (target - direction.0 + PI).rem_euclid(TAU) - PI;
+        let max_turn = config.turn_speed / config.tick_rate as f32;
+        intent.turn = (difference / max_turn).clamp(-1.0, 1.0);
+
+        // On the grid, turn once the cursor is closer to a side than ahead
+        if *movement_mode == MovementMode::Grid
+            && !grid_movement.has_queued_turns()
+            && !intent.has_turn_requests()
+        {
+            if difference > FRAC_PI_4 {
+                intent.request_turn(Turn::Left);
+            } else if difference < -FRAC_PI_4 {
+                intent.request_turn(Turn::Right);
+            }
+        }
+    }
+}
@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    components::{
+        player::{Player, PLAYER_PRESETS},
+        point_list::PointList,
+        power_ups::{DoubleScore, Ghost, Magnet, PowerUp, Shield, Shrink, SlowMotion},
+        snake::Snake,
+    },
+    config::SnakeConfig,
+    food::{prey::move_prey, Food},
+    game::GameSet,
+    levels::arena::Arena,
+    settings::GameSettings,
+    spatial::SpatialIndex,
+};
+
+/// Speed of the snakes slowed down by the slow motion of another snake
+const SLOW_MOTION_FACTOR: f32 = 0.5;
+
+/// Thickness of a shrunk snake, relative to the normal thickness
+const SHRINK_FACTOR: f32 = 0.5;
+
+/// Points of the foods eaten with a double score, relative to their score
+const DOUBLE_SCORE_FACTOR: f32 = 2.0;
+
+/// Distance from the head under which a magnet pulls the foods
+const MAGNET_RADIUS: f32 = 60.0;
+
+/// Speed of the foods pulled by a magnet, in units per second
+const MAGNET_SPEED: f32 = 80.0;
+
+pub struct PowerUpsPlugin;
+
+impl Plugin for PowerUpsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(AppState::InGame), setup_power_ups_ui)
+            .add_systems(OnExit(AppState::InGame), cleanup_power_ups_ui)
+            .add_systems(
+                Update,
+                update_power_ups_ui.run_if(in_state(AppState::InGame)),
+            )
+            .add_systems(
+                FixedUpdate,
+                (
+                    pull_foods.in_set(GameSet::Movement).after(move_prey),
+                    (
+                        tick_power_up::<Ghost>,
+                        tick_power_up::<SlowMotion>,
+                        tick_power_up::<Shrink>,
+                        tick_power_up::<Magnet>,
+                        tick_power_up::<DoubleScore>,
+                        tick_power_up::<Shield>,
+                    )
+                        .in_set(GameSet::Rules),
+                ),
+            );
+    }
+}
+
+/// Speed factor of a snake, slowed down when another snake has the slow motion
+pub fn speed_factor(snake: Entity, slow_motion_query: &Query<Entity, With<SlowMotion>>) -> f32 {
+    if slow_motion_query.iter().any(|other| other != snake) {
+        SLOW_MOTION_FACTOR
+    } else {
+        1.0
+    }
+}
+
+/// Thickness of a snake, thinner while shrunk
+pub fn thickness(shrunk: bool, config: &SnakeConfig) -> f32 {
+    if shrunk {
+        config.snake_thickness * SHRINK_FACTOR
+    } else {
+        config.snake_thickness
+    }
+}
+
+/// Factor applied to the points of a food, doubled with a double score
+pub fn score_factor(double_score: bool) -> f32 {
+    if double_score {
+        DOUBLE_SCORE_FACTOR
+    } else {
+        1.0
+    }
+}
+
+/// Remove the power-ups once they wear off
+fn tick_power_up<T: PowerUp>(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut T)>,
+) {
+    for (entity, mut power_up) in query.iter_mut() {
+        power_up.timer_mut().tick(time.delta());
+        if power_up.timer().finished() {
+            commands.entity(entity).remove::<T>();
+        }
+    }
+}
+
+/// Move the foods around the heads with a magnet toward them
+fn pull_foods(
+    time: Res<Time>,
+    arena: Res<Arena>,
+    food_index: Res<SpatialIndex<Food>>,
+    magnet_query: Query<&PointList, (With<Snake>, With<Magnet>)>,
+    mut food_query: Query<&mut Transform, With<Food>>,
+) {
+    let max_step = MAGNET_SPEED * time.delta_seconds();
+
+    for point_list in magnet_query.iter() {
+        let head = point_list.0[0];
+
+        for item in food_index.query(head, MAGNET_RADIUS) {
+            let Ok(mut transform) = food_query.get_mut(item.entity) else {
+                continue;
+            };
+
+            let to_head = arena.delta(transform.translation.xy(), head);
+            if to_head.length() > MAGNET_RADIUS {
+                continue;
+            }
+
+            let position =
+                arena.wrap(transform.translation.xy() + to_head.clamp_length_max(max_step));
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+}
+
+#[derive(Component)]
+struct PowerUpsUiRoot;
+
+#[derive(Component)]
+struct PowerUpsUiText;
+
+type PowerUpsQuery<'world, 'state, 'a> = Query<
+    'world,
+    'state,
+    (
+        &'a Player,
+        Option<&'a Ghost>,
+        Option<&'a SlowMotion>,
+        Option<&'a Shrink>,
+        Option<&'a Magnet>,
+        Option<&'a DoubleScore>,
+        Option<&'a Shield>,
+    ),
+    With<Snake>,
+>;
+
+/// One line per player under the lifes, listing the active power-ups
+fn setup_power_ups_ui(mut commands: Commands, settings: Res<GameSettings>) {
+    let sections = PLAYER_PRESETS
+        .iter()
+        .take(settings.total_players())
+        .map(|preset| TextSection {
+            value: String::new(),
+            style: TextStyle {
+                font_size: 16.0,
+                color: Color::from(preset.color),
+                ..default()
+            },
+        })
+        .collect::<Vec<_>>();
+
+    commands
+        .spawn((
+            PowerUpsUiRoot,
+            NodeBundle {
+                background_color: BackgroundColor(Color::BLACK.with_alpha(0.5)),
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(10.0),
+                    top: Val::Px(32.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                PowerUpsUiText,
+                TextBundle {
+                    text: Text::from_sections(sections),
+                    ..Default::default()
+                },
+            ));
+        });
+}
+
+fn label<T: PowerUp>(power_up: Option<&T>) -> Option<String> {
+    power_up.map(|power_up| {
+        format!(
+            " {} {:.0}s",
+            T::LABEL,
+            power_up.timer().remaining_secs().ceil()
+        )
+    })
+}
+
+fn update_power_ups_ui(
+    settings: Res<GameSettings>,
+    snake_query: PowerUpsQuery,
+    mut text_query: Query<&mut Text, With<PowerUpsUiText>>,
+    mut root_query: Query<&mut Visibility, With<PowerUpsUiRoot>>,
+) {
+    for mut text in &mut text_query {
+        for section in text.sections.iter_mut() {
+            section.value.clear();
+        }
+
+        for (player, ghost, slow_motion, shrink, magnet, double_score, shield) in snake_query.iter()
+        {
+            let labels: String = [
+                label(ghost),
+                label(slow_motion),
+                label(shrink),
+                label(magnet),
+                label(double_score),
+                label(shield),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            if labels.is_empty() {
+                continue;
+            }
+
+            let name = if settings.is_bot(player.0) {
+                "BOT"
+            } else {
+                PLAYER_PRESETS[player.0].name
+            };
+            if let Some(section) = text.sections.get_mut(player.0) {
+                section.value = format!("{name}:{labels}\n");
+            }
+        }
+
+        // No empty line after the last player listed
+        if let Some(last) = text
+            .sections
+            .iter_mut()
+            .rev()
+            .find(|section| !section.value.is_empty())
+        {
+            last.value.pop();
+        }
+
+        // Hide the panel while nobody has a power-up
+        let is_empty = text.sections.iter().all(|section| section.value.is_empty());
+        for mut visibility in &mut root_query {
+            *visibility = if is_empty {
+                Visibility::Hidden
+            } else {
+                Visibility::Inherited
+            };
+        }
+    }
+}
+
+fn cleanup_power_ups_ui(mut commands: Commands, query: Query<Entity, With<PowerUpsUiRoot>>) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
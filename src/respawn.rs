@@ -0,0 +1,221 @@
+use std::f32::consts::{FRAC_PI_2, PI, TAU};
+
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::{
+    app_state::GamePhase,
+    boost::Boost,
+    bots::Bot,
+    collision::apply_hits,
+    components::{
+        direction::Direction, invincible::Invincible, obstacle::Obstacle,
+        path_history::PathHistory, pending_growth::PendingGrowth, point_list::PointList,
+        snake::Snake,
+    },
+    config::SnakeConfig,
+    free_space::FreeSpace,
+    game::GameSet,
+    grid::GridMovement,
+    levels::{arena::Arena, MovementMode},
+    rng::GameRng,
+};
+
+/// Random positions tried when no spawn point of the level is safe
+const SPAWN_CANDIDATES: usize = 64;
+
+/// Distance a spawn should keep from obstacles, other snakes and solid walls
+const SAFE_DISTANCE: f32 = 30.0;
+
+/// Open space wanted in front of a respawned snake
+const LOOKAHEAD: f32 = 60.0;
+
+/// Directions considered for a respawned snake in the continuous mode
+const HEADINGS: usize = 16;
+
+pub struct RespawnPlugin;
+
+impl Plugin for RespawnPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Respawn>().add_systems(
+            FixedUpdate,
+            respawn_snakes.in_set(GameSet::Collision).after(apply_hits),
+        );
+    }
+}
+
+/// A snake lost a life and has to be rebuilt somewhere safe
+#[derive(Event, Debug)]
+pub struct Respawn {
+    pub snake: Entity,
+}
+
+/// Preferred places to respawn declared by the level
+#[derive(Resource, Default, Debug)]
+pub struct SpawnPoints(pub Vec<Vec2>);
+
+/// Safe places for the snakes spawned or respawned on the same tick
+pub struct SpawnArea<'a, 'w, 's> {
+    space: &'a FreeSpace<'w, 's>,
+    /// Snakes being rebuilt, their old bodies don't count
+    respawning: Vec<Entity>,
+    /// Bodies of the snakes already respawned, not in the index yet
+    taken: Vec<Vec2>,
+    config: &'a SnakeConfig,
+}
+
+impl<'a, 'w, 's> SpawnArea<'a, 'w, 's> {
+    pub fn new(
+        space: &'a FreeSpace<'w, 's>,
+        respawning: Vec<Entity>,
+        config: &'a SnakeConfig,
+    ) -> Self {
+        Self {
+            space,
+            respawning,
+            taken: Vec::new(),
+            config,
+        }
+    }
+
+    /// Pick a safe head position and direction for the next snake, and keep
+    /// its body clear of the next ones
+    pub fn place(
+        &mut self,
+        spawn_points: &[Vec2],
+        movement_mode: MovementMode,
+        rng: &mut impl Rng,
+    ) -> (Vec2, f32) {
+        let (head, direction) = self.find_spawn(spawn_points, movement_mode, rng);
+        // Snakes placed on the same tick must not overlap
+        let body = Snake::initial_points(head, direction, self.space.arena(), self.config);
+        self.taken.extend(body);
+        (head, direction)
+    }
+
+    fn clearance(&self, point: Vec2) -> f32 {
+        self.space.clearance(point, &self.respawning, &self.taken)
+    }
+
+    /// Distance that can be travelled from `from` along `angle` while staying
+    /// clear of any danger, capped to `max`
+    fn free_distance(&self, from: Vec2, angle: f32, max: f32) -> f32 {
+        let heading = Vec2::from_angle(angle);
+        let step = self.config.snake_thickness;
+        let margin = self.config.snake_thickness * 2.0;
+
+        let mut distance = step;
+        while distance < max {
+            let point = self.space.arena().wrap(from + heading * distance);
+            if self.clearance(point) < margin {
+                return distance;
+            }
+            distance += step;
+        }
+
+        max
+    }
+
+    /// Direction facing the most open space with room for the body behind
+    /// the head, if there is any
+    fn heading(&self, head: Vec2, movement_mode: MovementMode) -> Option<f32> {
+        let (count, step) = match movement_mode {
+            MovementMode::Continuous => (HEADINGS, TAU / HEADINGS as f32),
+            MovementMode::Grid => (4, FRAC_PI_2),
+        };
+        let body_length = self.config.initial_length as f32 * self.config.distance_between_points;
+
+        (0..count)
+            .map(|i| i as f32 * step)
+            .filter(|angle| self.free_distance(head, angle + PI, body_length) >= body_length)
+            .map(|angle| (angle, self.free_distance(head, angle, LOOKAHEAD)))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(angle, _)| angle)
+    }
+
+    /// Pick a safe head position and direction, trying the spawn points of
+    /// the level first, then random places from the safest to the least safe
+    fn find_spawn(
+        &self,
+        spawn_points: &[Vec2],
+        movement_mode: MovementMode,
+        rng: &mut impl Rng,
+    ) -> (Vec2, f32) {
+        let preferred: Vec<(Vec2, f32)> = self
+            .space
+            .rank(
+                spawn_points.iter().map(|point| self.space.snap(*point)),
+                &self.respawning,
+                &self.taken,
+            )
+            .into_iter()
+            .filter(|(_, clearance)| *clearance >= SAFE_DISTANCE)
+            .collect();
+        let random = self.space.rank(
+            self.space.random_points(SPAWN_CANDIDATES, rng),
+            &self.respawning,
+            &self.taken,
+        );
+
+        for (point, _) in preferred.iter().chain(random.iter()) {
+            if let Some(direction) = self.heading(*point, movement_mode) {
+                return (*point, direction);
+            }
+        }
+
+        warn!("No safe place found to respawn, using the center of the arena");
+        let center = self.space.center();
+        (center, self.heading(center, movement_mode).unwrap_or(0.0))
+    }
+}
+
+type RespawnSnakeQuery<'world, 'state, 'a> =
+    Query<'world, 'state, (Entity, &'a PointList, Has<Bot>), (With<Snake>, Without<Obstacle>)>;
+
+/// Rebuild the snakes that lost a life at a safe place, then protect them for
+/// a moment and give the players a countdown to get ready
+#[allow(clippy::too_many_arguments)]
+fn respawn_snakes(
+    mut commands: Commands,
+    mut respawns: EventReader<Respawn>,
+    arena: Res<Arena>,
+    config: Res<SnakeConfig>,
+    movement_mode: Res<MovementMode>,
+    spawn_points: Res<SpawnPoints>,
+    snake_query: RespawnSnakeQuery,
+    free_space: FreeSpace,
+    mut rng: ResMut<GameRng>,
+    mut next_phase: ResMut<NextState<GamePhase>>,
+) {
+    let respawning: Vec<Entity> = respawns.read().map(|respawn| respawn.snake).collect();
+    if respawning.is_empty() {
+        return;
+    }
+
+    let mut area = SpawnArea::new(&free_space, respawning.clone(), &config);
+
+    let mut human_respawned = false;
+    for entity in respawning {
+        let Ok((_, _, is_bot)) = snake_query.get(entity) else {
+            continue;
+        };
+        human_respawned |= !is_bot;
+
+        let (head, direction) = area.place(&spawn_points.0, *movement_mode, &mut rng.0);
+        let points = Snake::initial_points(head, direction, &arena, &config);
+
+        commands.entity(entity).insert((
+            PathHistory::from_points(&points),
+            PointList(points),
+            Direction(direction),
+            Boost::default(),
+            GridMovement::default(),
+            PendingGrowth::default(),
+            Invincible::new(config.spawn_protection_duration),
+        ));
+    }
+
+    if human_respawned {
+        next_phase.set(GamePhase::Countdown);
+    }
+}
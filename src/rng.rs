@@ -0,0 +1,33 @@
+use bevy::prelude::*;
+use rand::{rngs::StdRng, SeedableRng};
+
+use crate::{app_state::AppState, game};
+
+/// Seed of every round, the same inputs give the same game
+const ROUND_SEED: u64 = 0;
+
+pub struct GameRngPlugin;
+
+impl Plugin for GameRngPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(GameRng::new()).add_systems(
+            OnEnter(AppState::InGame),
+            reset_game_rng.before(game::setup),
+        );
+    }
+}
+
+/// Randomness of the simulation, like where foods show up and how preys
+/// wander. Seeded again at the start of each round.
+#[derive(Resource)]
+pub struct GameRng(pub StdRng);
+
+impl GameRng {
+    fn new() -> Self {
+        Self(StdRng::seed_from_u64(ROUND_SEED))
+    }
+}
+
+fn reset_game_rng(mut rng: ResMut<GameRng>) {
+    *rng = GameRng::new();
+}
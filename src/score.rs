@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+
+use crate::{
+    components::{player::Player, point_list::PointList, power_ups::DoubleScore, snake::Snake},
+    config::SnakeConfig,
+    food::{food_collision_system, FoodEaten},
+    game::GameSet,
+    power_ups::score_factor,
+};
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            FixedUpdate,
+            (
+                award_points
+                    .in_set(GameSet::Collision)
+                    .after(food_collision_system),
+                decay_combos.in_set(GameSet::Rules),
+            ),
+        );
+    }
+}
+
+/// Score of a player and its current combo
+#[derive(Clone, Copy, Debug)]
+pub struct PlayerScore {
+    pub points: u32,
+    /// Multiplier of the next food, built by eating in quick succession
+    pub combo: f32,
+}
+
+impl Default for PlayerScore {
+    fn default() -> Self {
+        Self {
+            points: 0,
+            combo: 1.0,
+        }
+    }
+}
+
+/// Score of each player, indexed by [`Player`], kept after the round for the
+/// game over screen
+#[derive(Resource, Debug)]
+pub struct PlayersScores(pub Vec<PlayerScore>);
+
+impl PlayersScores {
+    pub fn new(player_count: usize) -> Self {
+        Self(vec![PlayerScore::default(); player_count])
+    }
+}
+
+/// Score the foods eaten: the score of the food plus a bonus for the length
+/// of the snake, multiplied by the combo, then the combo grows
+fn award_points(
+    mut eaten_events: EventReader<FoodEaten>,
+    snake_query: Query<(&Player, &PointList, Has<DoubleScore>), With<Snake>>,
+    mut scores: ResMut<PlayersScores>,
+    config: Res<SnakeConfig>,
+) {
+    for eaten in eaten_events.read() {
+        let Ok((player, point_list, double_score)) = snake_query.get(eaten.snake) else {
+            continue;
+        };
+        let Some(score) = scores.0.get_mut(player.0) else {
+            continue;
+        };
+
+        let length_bonus = point_list.0.len() as f32 * config.length_bonus;
+        let points = (eaten.score as f32 + length_bonus) * score.combo * score_factor(double_score);
+
+        score.points += points.round() as u32;
+        score.combo = (score.combo + config.combo_step).min(config.combo_max.max(1.0));
+    }
+}
+
+/// The combo fades back to 1 when the player stops eating
+fn decay_combos(time: Res<Time>, mut scores: ResMut<PlayersScores>, config: Res<SnakeConfig>) {
+    let decay = config.combo_decay * time.delta_seconds();
+
+    for score in scores.0.iter_mut() {
+        score.combo = (score.combo - decay).max(1.0);
+    }
+}
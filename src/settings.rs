@@ -0,0 +1,111 @@
+use bevy::prelude::*;
+
+use crate::components::player::MAX_PLAYERS;
+
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<GameSettings>();
+    }
+}
+
+/// How the round ends when several players are in the game
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GameMode {
+    /// The round ends when a single player remains
+    #[default]
+    Versus,
+    /// The round ends when every player is dead
+    Coop,
+}
+
+impl GameMode {
+    pub fn label(&self) -> &'static str {
+        match self {
+            GameMode::Versus => "VERSUS",
+            GameMode::Coop => "CO-OP",
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        *self = match self {
+            GameMode::Versus => GameMode::Coop,
+            GameMode::Coop => GameMode::Versus,
+        };
+    }
+}
+
+/// How the first player steers their snake
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ControlScheme {
+    /// Turn left and right with the keys of the player
+    #[default]
+    Keys,
+    /// Turn towards the mouse cursor
+    Pointer,
+}
+
+impl ControlScheme {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ControlScheme::Keys => "KEYS",
+            ControlScheme::Pointer => "MOUSE",
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        *self = match self {
+            ControlScheme::Keys => ControlScheme::Pointer,
+            ControlScheme::Pointer => ControlScheme::Keys,
+        };
+    }
+}
+
+/// Options chosen in the main menu
+#[derive(Resource, Debug)]
+pub struct GameSettings {
+    /// Players on the keyboard, they come first
+    pub player_count: usize,
+    /// Computer-controlled snakes, after the human players
+    pub bot_count: usize,
+    pub mode: GameMode,
+    /// There is a single mouse, so only the first player can use it
+    pub control_scheme: ControlScheme,
+}
+
+impl Default for GameSettings {
+    fn default() -> Self {
+        Self {
+            player_count: 1,
+            bot_count: 0,
+            mode: GameMode::default(),
+            control_scheme: ControlScheme::default(),
+        }
+    }
+}
+
+impl GameSettings {
+    pub fn total_players(&self) -> usize {
+        self.player_count + self.bot_count
+    }
+
+    pub fn is_bot(&self, player: usize) -> bool {
+        player >= self.player_count
+    }
+
+    pub fn uses_pointer(&self, player: usize) -> bool {
+        player == 0 && self.control_scheme == ControlScheme::Pointer
+    }
+
+    /// Go to the next player count, back to one after the maximum
+    pub fn cycle_player_count(&mut self) {
+        self.player_count = self.player_count % MAX_PLAYERS + 1;
+        self.bot_count = self.bot_count.min(MAX_PLAYERS - self.player_count);
+    }
+
+    /// Go to the next bot count, back to none once every slot is taken
+    pub fn cycle_bot_count(&mut self) {
+        self.bot_count = (self.bot_count + 1) % (MAX_PLAYERS - self.player_count + 1);
+    }
+}
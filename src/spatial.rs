@@ -0,0 +1,444 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use bevy::prelude::*;
+
+use crate::{
+    app_state::AppState,
+    collision::collision_system,
+    components::{obstacle::Obstacle, point_list::PointList, snake::Snake},
+    food::{self, Food},
+    game::{self, GameSet},
+    levels::arena::{Arena, BoundaryMode},
+};
+
+/// Wanted size of a cell, adjusted so that a whole number of cells fits in
+/// the arena
+const CELL_SIZE: f32 = 32.0;
+
+pub struct SpatialPlugin;
+
+impl Plugin for SpatialPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(AppState::InGame),
+            (
+                setup_indexes.after(game::setup),
+                // The first snakes have to be known before the first foods
+                // are placed
+                index_snakes
+                    .after(game::spawn_snakes)
+                    .before(food::spawn_food),
+            ),
+        )
+        .add_systems(OnExit(AppState::InGame), cleanup_indexes)
+        .add_systems(
+            FixedUpdate,
+            (index_obstacles, index_snakes, index_foods)
+                .in_set(GameSet::Collision)
+                .before(collision_system),
+        )
+        .observe(forget_removed::<Obstacle>)
+        .observe(forget_removed::<Snake>)
+        .observe(forget_removed::<Food>);
+    }
+}
+
+/// Something stored in the index: an entity, and which of its parts when it
+/// has several, like the segments of a snake body
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct IndexItem {
+    pub entity: Entity,
+    pub part: usize,
+}
+
+/// Uniform grid of the arena telling which `T` entities lie in each cell.
+///
+/// Queries only look at the few cells around a point instead of every
+/// entity. Entities are dropped as soon as they lose their `T` or are
+/// despawned.
+///
+/// Every part remembers the cells it covers, so a moved part only leaves
+/// the cells it no longer covers and enters the new ones. The bodies of the
+/// snakes move on every tick, each segment is still compared with its cells
+/// then, but the grid itself only changes around the heads, the tails and
+/// the segments crossing a cell border.
+#[derive(Resource)]
+pub struct SpatialIndex<T> {
+    grid: Grid,
+    cells: HashMap<IVec2, Vec<IndexItem>>,
+    /// Cells covered by each part of each entity, indexed by part
+    entity_parts: HashMap<Entity, Vec<CellRect>>,
+    marker: PhantomData<T>,
+}
+
+/// Cells covered by a part, before wrapping
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CellRect {
+    min: IVec2,
+    max: IVec2,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Grid {
+    arena: Arena,
+    cell_size: Vec2,
+    /// Number of cells along each axis, used to wrap the cells around
+    cell_count: IVec2,
+}
+
+impl Grid {
+    /// Cell containing the point, before wrapping
+    fn cell(&self, point: Vec2) -> IVec2 {
+        ((point - self.arena.min) / self.cell_size)
+            .floor()
+            .as_ivec2()
+    }
+
+    fn rect(&self, min: Vec2, max: Vec2) -> CellRect {
+        CellRect {
+            min: self.cell(min),
+            max: self.cell(max),
+        }
+    }
+
+    /// Cells of the rectangle, across the seam when the arena wraps
+    fn cells(self, rect: CellRect) -> impl Iterator<Item = IVec2> {
+        (rect.min.y..=rect.max.y)
+            .flat_map(move |y| (rect.min.x..=rect.max.x).map(move |x| IVec2::new(x, y)))
+            .map(move |cell| match self.arena.boundary {
+                BoundaryMode::Solid => cell,
+                BoundaryMode::Wrap => cell.rem_euclid(self.cell_count),
+            })
+    }
+}
+
+fn enter(cells: &mut HashMap<IVec2, Vec<IndexItem>>, cell: IVec2, item: IndexItem) {
+    let items = cells.entry(cell).or_default();
+    if !items.contains(&item) {
+        items.push(item);
+    }
+}
+
+fn leave(cells: &mut HashMap<IVec2, Vec<IndexItem>>, cell: IVec2, item: IndexItem) {
+    let Some(items) = cells.get_mut(&cell) else {
+        return;
+    };
+    if let Some(index) = items.iter().position(|other| *other == item) {
+        items.swap_remove(index);
+    }
+    if items.is_empty() {
+        cells.remove(&cell);
+    }
+}
+
+impl<T> SpatialIndex<T> {
+    pub fn new(arena: &Arena) -> Self {
+        let cell_count = (arena.size() / CELL_SIZE).ceil().max(Vec2::ONE);
+        Self {
+            grid: Grid {
+                arena: *arena,
+                cell_size: arena.size() / cell_count,
+                cell_count: cell_count.as_ivec2(),
+            },
+            cells: HashMap::new(),
+            entity_parts: HashMap::new(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Store the parts of the entity, one rectangle per part, or move them
+    /// when they are already stored. The parts past the last one are
+    /// forgotten, for a snake that got shorter.
+    pub fn update(&mut self, entity: Entity, rects: impl IntoIterator<Item = (Vec2, Vec2)>) {
+        let grid = self.grid;
+        let parts = self.entity_parts.entry(entity).or_default();
+
+        let mut count = 0;
+        for (part, (min, max)) in rects.into_iter().enumerate() {
+            count += 1;
+            let rect = grid.rect(min, max);
+            let item = IndexItem { entity, part };
+
+            let Some(old) = parts.get_mut(part) else {
+                parts.push(rect);
+                for cell in grid.cells(rect) {
+                    enter(&mut self.cells, cell, item);
+                }
+                continue;
+            };
+            if *old == rect {
+                continue;
+            }
+
+            let old_cells: Vec<IVec2> = grid.cells(*old).collect();
+            let new_cells: Vec<IVec2> = grid.cells(rect).collect();
+            for cell in old_cells.iter().filter(|cell| !new_cells.contains(cell)) {
+                leave(&mut self.cells, *cell, item);
+            }
+            for cell in new_cells.iter().filter(|cell| !old_cells.contains(cell)) {
+                enter(&mut self.cells, *cell, item);
+            }
+            *old = rect;
+        }
+
+        for (part, rect) in parts.drain(count..).enumerate() {
+            let item = IndexItem {
+                entity,
+                part: count + part,
+            };
+            for cell in grid.cells(rect) {
+                leave(&mut self.cells, cell, item);
+            }
+        }
+        if parts.is_empty() {
+            self.entity_parts.remove(&entity);
+        }
+    }
+
+    /// Forget every part of the entity
+    pub fn remove(&mut self, entity: Entity) {
+        self.update(entity, []);
+    }
+
+    /// Every item stored around the point, some of them may be further than
+    /// `radius` and have to be checked by the caller
+    pub fn query(&self, point: Vec2, radius: f32) -> Vec<IndexItem> {
+        let rect = self.grid.rect(point - radius, point + radius);
+        let mut items: Vec<IndexItem> = self
+            .grid
+            .cells(rect)
+            .filter_map(|cell| self.cells.get(&cell))
+            .flatten()
+            .copied()
+            .collect();
+
+        // Items spanning several cells are found once per cell
+        items.sort_unstable();
+        items.dedup();
+        items
+    }
+}
+
+impl SpatialIndex<Obstacle> {
+    /// Obstacles are stored by bounding box
+    pub fn insert_polygon(&mut self, entity: Entity, polygon: &[Vec2]) {
+        let bounds = polygon
+            .iter()
+            .copied()
+            .reduce(Vec2::min)
+            .zip(polygon.iter().copied().reduce(Vec2::max));
+        self.update(entity, bounds);
+    }
+}
+
+impl SpatialIndex<Snake> {
+    /// Every segment of the body is stored, the part being the index of the
+    /// first point of the segment
+    pub fn insert_body(&mut self, entity: Entity, points: &[Vec2]) {
+        let arena = self.grid.arena;
+        let segments = points.windows(2).map(|segment| {
+            // Keep the segment in one piece when it lies across the seam
+            let a = segment[0];
+            let b = a + arena.delta(a, segment[1]);
+            (a.min(b), a.max(b))
+        });
+        self.update(entity, segments);
+    }
+}
+
+impl SpatialIndex<Food> {
+    pub fn insert_point(&mut self, entity: Entity, point: Vec2) {
+        self.update(entity, [(point, point)]);
+    }
+}
+
+/// Fresh indexes for the arena of the new level, holding what the level
+/// spawned so the places of the first snakes and foods can be looked up
+pub fn setup_indexes(
+    mut commands: Commands,
+    arena: Res<Arena>,
+    obstacle_query: Query<(Entity, &PointList), With<Obstacle>>,
+    snake_query: Query<(Entity, &PointList), With<Snake>>,
+    food_query: Query<(Entity, &Transform), With<Food>>,
+) {
+    let mut obstacle_index = SpatialIndex::<Obstacle>::new(&arena);
+    for (entity, point_list) in obstacle_query.iter() {
+        obstacle_index.insert_polygon(entity, &point_list.0);
+    }
+    let mut snake_index = SpatialIndex::<Snake>::new(&arena);
+    for (entity, point_list) in snake_query.iter() {
+        snake_index.insert_body(entity, &point_list.0);
+    }
+    let mut food_index = SpatialIndex::<Food>::new(&arena);
+    for (entity, transform) in food_query.iter() {
+        food_index.insert_point(entity, transform.translation.xy());
+    }
+
+    commands.insert_resource(obstacle_index);
+    commands.insert_resource(snake_index);
+    commands.insert_resource(food_index);
+}
+
+fn cleanup_indexes(mut commands: Commands) {
+    commands.remove_resource::<SpatialIndex<Obstacle>>();
+    commands.remove_resource::<SpatialIndex<Snake>>();
+    commands.remove_resource::<SpatialIndex<Food>>();
+}
+
+/// Drop the entity from the index right away, removal events can be missed
+/// when nothing reads them before they expire
+fn forget_removed<T: Component>(
+    trigger: Trigger<OnRemove, T>,
+    index: Option<ResMut<SpatialIndex<T>>>,
+) {
+    if let Some(mut index) = index {
+        index.remove(trigger.entity());
+    }
+}
+
+/// Obstacles never move, they are indexed when they appear
+fn index_obstacles(
+    mut index: ResMut<SpatialIndex<Obstacle>>,
+    query: Query<(Entity, &PointList), Added<Obstacle>>,
+) {
+    for (entity, point_list) in query.iter() {
+        index.insert_polygon(entity, &point_list.0);
+    }
+}
+
+type MovedSnakeQuery<'world, 'state, 'a> =
+    Query<'world, 'state, (Entity, &'a PointList), (With<Snake>, Changed<PointList>)>;
+
+type MovedFoodQuery<'world, 'state, 'a> =
+    Query<'world, 'state, (Entity, &'a Transform), (With<Food>, Changed<Transform>)>;
+
+fn index_snakes(mut index: ResMut<SpatialIndex<Snake>>, query: MovedSnakeQuery) {
+    for (entity, point_list) in query.iter() {
+        index.insert_body(entity, &point_list.0);
+    }
+}
+
+fn index_foods(mut index: ResMut<SpatialIndex<Food>>, query: MovedFoodQuery) {
+    for (entity, transform) in query.iter() {
+        index.insert_point(entity, transform.translation.xy());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arena(boundary: BoundaryMode) -> Arena {
+        Arena {
+            min: Vec2::new(-640.0, -360.0),
+            max: Vec2::new(640.0, 360.0),
+            boundary,
+        }
+    }
+
+    fn item(index: u32) -> IndexItem {
+        IndexItem {
+            entity: Entity::from_raw(index),
+            part: 0,
+        }
+    }
+
+    fn entity(index: u32) -> Entity {
+        Entity::from_raw(index)
+    }
+
+    #[test]
+    fn remove_forgets_every_cell() {
+        let mut index = SpatialIndex::<Snake>::new(&arena(BoundaryMode::Solid));
+        index.insert_body(
+            entity(1),
+            &[Vec2::new(-100.0, -100.0), Vec2::ZERO, Vec2::new(50.0, 0.0)],
+        );
+        assert!(!index.cells.is_empty());
+
+        index.remove(entity(1));
+
+        assert!(index.cells.is_empty());
+        assert!(index.entity_parts.is_empty());
+    }
+
+    #[test]
+    fn moved_parts_only_change_their_cells() {
+        let mut index = SpatialIndex::<Snake>::new(&arena(BoundaryMode::Solid));
+        let body = [
+            Vec2::new(1.0, 1.0),
+            Vec2::new(5.0, 1.0),
+            Vec2::new(9.0, 1.0),
+        ];
+        index.insert_body(entity(1), &body);
+        let cells_before = index.cells.clone();
+
+        // Still in the same cell
+        index.insert_body(entity(1), &body.map(|point| point + Vec2::X));
+        assert_eq!(index.cells, cells_before);
+
+        // The head crosses into the next cell, the tail stays
+        let moved = [
+            Vec2::new(40.0, 1.0),
+            Vec2::new(9.0, 1.0),
+            Vec2::new(5.0, 1.0),
+        ];
+        index.insert_body(entity(1), &moved);
+        let head = IndexItem {
+            entity: entity(1),
+            part: 0,
+        };
+        assert!(index.query(Vec2::new(40.0, 1.0), 1.0).contains(&head));
+        assert_eq!(
+            index.cells.get(&index.grid.cell(Vec2::new(40.0, 1.0))),
+            Some(&vec![head])
+        );
+    }
+
+    #[test]
+    fn shorter_bodies_drop_their_last_parts() {
+        let mut index = SpatialIndex::<Snake>::new(&arena(BoundaryMode::Solid));
+        let tail = Vec2::new(200.0, 200.0);
+        index.insert_body(entity(1), &[Vec2::ZERO, Vec2::new(4.0, 0.0), tail]);
+        assert_eq!(index.query(tail, 1.0).len(), 1);
+
+        index.insert_body(entity(1), &[Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+
+        assert!(index.query(tail, 1.0).is_empty());
+        assert_eq!(index.entity_parts[&entity(1)].len(), 1);
+    }
+
+    #[test]
+    fn query_wraps_around_the_seam() {
+        let mut index = SpatialIndex::<Food>::new(&arena(BoundaryMode::Wrap));
+        index.insert_point(entity(1), Vec2::new(638.0, 0.0));
+
+        assert_eq!(index.query(Vec2::new(-638.0, 0.0), 8.0), vec![item(1)]);
+        assert!(index.query(Vec2::new(-400.0, 0.0), 8.0).is_empty());
+    }
+
+    #[test]
+    fn query_stops_at_solid_walls() {
+        let mut index = SpatialIndex::<Food>::new(&arena(BoundaryMode::Solid));
+        index.insert_point(entity(1), Vec2::new(638.0, 0.0));
+
+        assert!(index.query(Vec2::new(-638.0, 0.0), 8.0).is_empty());
+    }
+
+    #[test]
+    fn despawned_entities_are_dropped() {
+        let mut world = World::new();
+        world.insert_resource(SpatialIndex::<Food>::new(&arena(BoundaryMode::Solid)));
+        world.observe(forget_removed::<Food>);
+        let entity = world.spawn(Food).id();
+        world
+            .resource_mut::<SpatialIndex<Food>>()
+            .insert_point(entity, Vec2::ZERO);
+
+        world.despawn(entity);
+
+        let index = world.resource::<SpatialIndex<Food>>();
+        assert!(index.cells.is_empty());
+        assert!(index.entity_parts.is_empty());
+    }
+}
@@ -10,6 +10,8 @@ use bevy::{
 };
 use bevy_egui::{egui, EguiContexts, EguiPlugin};
 
+use crate::controls::ActionMap;
+
 #[derive(Default)]
 pub struct SteppingEguiPlugin {
     schedule_labels: Vec<InternedScheduleLabel>,
@@ -240,9 +242,15 @@ fn draw_window(
     });
 }
 
-fn handle_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut stepping: ResMut<Stepping>) {
-    // grave key to toggle stepping mode for the FixedUpdate schedule
-    if keyboard_input.just_pressed(KeyCode::Backquote) {
+fn handle_input(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
+    mut stepping: ResMut<Stepping>,
+) {
+    let keys = &action_map.stepping;
+
+    // grave key (by default) to toggle stepping mode for the FixedUpdate schedule
+    if keyboard_input.just_pressed(keys.toggle) {
         if stepping.is_enabled() {
             stepping.disable();
         } else {
@@ -254,23 +262,24 @@ fn handle_input(keyboard_input: Res<ButtonInput<KeyCode>>, mut stepping: ResMut<
         return;
     }
 
-    // space key will step the remainder of this frame
-    if keyboard_input.just_pressed(KeyCode::Space) {
+    // space key (by default) will step the remainder of this frame
+    if keyboard_input.just_pressed(keys.continue_frame) {
         stepping.continue_frame();
-    } else if keyboard_input.just_pressed(KeyCode::KeyS) {
+    } else if keyboard_input.just_pressed(keys.step_frame) {
         stepping.step_frame();
 
-    // hold enter to continue as fast as possible
-    } else if keyboard_input.pressed(KeyCode::Enter) {
+    // hold enter (by default) to continue as fast as possible
+    } else if keyboard_input.pressed(keys.run) {
         stepping.continue_frame();
     }
 }
 
 fn toggle_ui_stepping(
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    action_map: Res<ActionMap>,
     mut ui_state: ResMut<SteppingUiState>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::F11) {
+    if keyboard_input.just_pressed(action_map.stepping.toggle_ui) {
         ui_state.toggle_visibility();
     }
 }
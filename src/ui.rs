@@ -1,35 +1,78 @@
 use bevy::{color::palettes::tailwind, prelude::*};
 
-use crate::app_state::AppState;
+use crate::{app_state::AppState, settings::GameSettings, ui_bindings::BindingsScreen};
 
 pub struct UIPlugin;
 
 impl Plugin for UIPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(AppState::MainMenu), create_ui)
+        app.add_systems(Startup, spawn_camera)
+            .add_systems(OnEnter(AppState::MainMenu), create_ui)
             .add_systems(OnExit(AppState::MainMenu), cleanup_ui)
             .add_systems(
                 Update,
-                button_interaction_system.run_if(in_state(AppState::MainMenu)),
+                (
+                    play_button_interaction_system,
+                    players_button_interaction_system,
+                    bots_button_interaction_system,
+                    mode_button_interaction_system,
+                    controls_button_interaction_system,
+                    bindings_button_interaction_system,
+                    update_settings_text.run_if(resource_changed::<GameSettings>),
+                )
+                    .run_if(in_state(AppState::MainMenu)),
             );
     }
 }
 
-const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+pub const NORMAL_BUTTON: Color = Color::srgb(0.15, 0.15, 0.15);
+
+/// The camera rendering the game and every screen
+#[derive(Component)]
+pub struct MainCamera;
+
+/// Root of a menu that other screens can be shown over
+#[derive(Component)]
+pub struct Menu;
 
 #[derive(Component)]
 struct MainMenuUi;
 
-/// Create a simple UI with Play Button that set AppState to InGame
-fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera2dBundle::default());
+#[derive(Component)]
+struct PlayButton;
+
+#[derive(Component)]
+struct PlayersButton;
+
+#[derive(Component)]
+struct BotsButton;
+
+#[derive(Component)]
+struct ModeButton;
+
+#[derive(Component)]
+struct ControlsButton;
+
+#[derive(Component)]
+struct BindingsButton;
+
+/// Text of a settings button, refreshed when the settings change
+#[derive(Component)]
+struct SettingText(fn(&GameSettings) -> String);
+
+/// Create a simple UI with Play Button that set AppState to InGame, and
+/// buttons to choose the number of players and bots, the game mode and the
+/// controls of the first player, and a button to the key bindings
+fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>, settings: Res<GameSettings>) {
     commands
         .spawn((
             MainMenuUi,
+            Menu,
             NodeBundle {
                 style: Style {
                     width: Val::Percent(100.0),
                     height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
                     align_items: AlignItems::Center,
                     justify_content: JustifyContent::Center,
                     ..default()
@@ -38,36 +81,116 @@ fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
             },
         ))
         .with_children(|parent| {
-            parent
-                .spawn(ButtonBundle {
-                    style: Style {
-                        width: Val::Px(150.0),
-                        height: Val::Px(65.0),
-                        border: UiRect::all(Val::Px(5.0)),
-                        // horizontally center child text
-                        justify_content: JustifyContent::Center,
-                        // vertically center child text
-                        align_items: AlignItems::Center,
-                        ..default()
-                    },
-                    border_color: BorderColor(Color::BLACK),
-                    background_color: NORMAL_BUTTON.into(),
+            let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+            spawn_button(parent, PlayButton, (), "PLAY".into(), 150.0, &font);
+            spawn_button(
+                parent,
+                PlayersButton,
+                SettingText(players_label),
+                players_label(&settings),
+                300.0,
+                &font,
+            );
+            spawn_button(
+                parent,
+                BotsButton,
+                SettingText(bots_label),
+                bots_label(&settings),
+                300.0,
+                &font,
+            );
+            spawn_button(
+                parent,
+                ModeButton,
+                SettingText(mode_label),
+                mode_label(&settings),
+                300.0,
+                &font,
+            );
+            spawn_button(
+                parent,
+                ControlsButton,
+                SettingText(controls_label),
+                controls_label(&settings),
+                300.0,
+                &font,
+            );
+            spawn_button(
+                parent,
+                BindingsButton,
+                (),
+                "KEY BINDINGS".into(),
+                300.0,
+                &font,
+            );
+        });
+}
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((MainCamera, Camera2dBundle::default()));
+}
+
+pub fn spawn_button(
+    parent: &mut ChildBuilder,
+    button: impl Bundle,
+    text: impl Bundle,
+    label: String,
+    width: f32,
+    font: &Handle<Font>,
+) {
+    parent
+        .spawn((
+            button,
+            ButtonBundle {
+                style: Style {
+                    margin: UiRect::bottom(Val::Px(10.0)),
+                    width: Val::Px(width),
+                    height: Val::Px(65.0),
+                    border: UiRect::all(Val::Px(5.0)),
+                    // horizontally center child text
+                    justify_content: JustifyContent::Center,
+                    // vertically center child text
+                    align_items: AlignItems::Center,
                     ..default()
-                })
-                .with_children(|parent| {
-                    parent.spawn(TextBundle::from_section(
-                        "PLAY",
-                        TextStyle {
-                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
-                            font_size: 40.0,
-                            color: Color::srgb(0.9, 0.9, 0.9),
-                        },
-                    ));
-                });
+                },
+                border_color: BorderColor(Color::BLACK),
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                text,
+                TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 40.0,
+                        color: Color::srgb(0.9, 0.9, 0.9),
+                    },
+                ),
+            ));
         });
 }
 
-type BtnInteractionQuery<'world, 'state, 'a, 'b, 'c, 'd> = Query<
+fn players_label(settings: &GameSettings) -> String {
+    format!("PLAYERS: {}", settings.player_count)
+}
+
+fn bots_label(settings: &GameSettings) -> String {
+    format!("BOTS: {}", settings.bot_count)
+}
+
+fn mode_label(settings: &GameSettings) -> String {
+    format!("MODE: {}", settings.mode.label())
+}
+
+fn controls_label(settings: &GameSettings) -> String {
+    format!("P1: {}", settings.control_scheme.label())
+}
+
+pub type BtnInteractionQuery<'world, 'state, 'a, 'b, 'c, 'd, T> = Query<
     'world,
     'state,
     (
@@ -76,29 +199,99 @@ type BtnInteractionQuery<'world, 'state, 'a, 'b, 'c, 'd> = Query<
         &'c mut BorderColor,
         &'d Children,
     ),
-    (Changed<Interaction>, With<Button>),
+    (Changed<Interaction>, With<Button>, With<T>),
 >;
 
-fn button_interaction_system(
-    mut interaction_query: BtnInteractionQuery,
+/// Highlight the border of the button, returns whether it was pressed
+pub fn update_button_border(interaction: &Interaction, border_color: &mut BorderColor) -> bool {
+    match *interaction {
+        Interaction::Pressed => {
+            border_color.0 = Color::from(tailwind::RED_500);
+            true
+        }
+        Interaction::Hovered => {
+            border_color.0 = Color::WHITE;
+            false
+        }
+        Interaction::None => {
+            border_color.0 = Color::BLACK;
+            false
+        }
+    }
+}
+
+fn play_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<PlayButton>,
     mut next_state: ResMut<NextState<AppState>>,
 ) {
     for (interaction, _, mut border_color, _) in &mut interaction_query {
-        match *interaction {
-            Interaction::Pressed => {
-                border_color.0 = Color::from(tailwind::RED_500);
-                next_state.set(AppState::InGame);
-            }
-            Interaction::Hovered => {
-                border_color.0 = Color::WHITE;
-            }
-            Interaction::None => {
-                border_color.0 = Color::BLACK;
-            }
+        if update_button_border(interaction, &mut border_color) {
+            next_state.set(AppState::InGame);
+        }
+    }
+}
+
+fn players_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<PlayersButton>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            settings.cycle_player_count();
+        }
+    }
+}
+
+fn bots_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<BotsButton>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            settings.cycle_bot_count();
+        }
+    }
+}
+
+fn mode_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<ModeButton>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            settings.mode.toggle();
+        }
+    }
+}
+
+fn controls_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<ControlsButton>,
+    mut settings: ResMut<GameSettings>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            settings.control_scheme.toggle();
         }
     }
 }
 
+fn bindings_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<BindingsButton>,
+    mut next_state: ResMut<NextState<BindingsScreen>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            next_state.set(BindingsScreen::Open);
+        }
+    }
+}
+
+fn update_settings_text(settings: Res<GameSettings>, mut query: Query<(&mut Text, &SettingText)>) {
+    for (mut text, label) in &mut query {
+        text.sections[0].value = (label.0)(&settings);
+    }
+}
+
 fn cleanup_ui(mut commands: Commands, query: Query<Entity, With<MainMenuUi>>) {
     for entity in &mut query.iter() {
         commands.entity(entity).despawn_recursive();
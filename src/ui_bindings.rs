@@ -0,0 +1,340 @@
+use bevy::{color::palettes::tailwind, prelude::*};
+
+use crate::{
+    controls::{Action, ActionMap},
+    gamepad::MenuBack,
+    ui::{spawn_button, update_button_border, BtnInteractionQuery, Menu, NORMAL_BUTTON},
+};
+
+pub struct UIBindingsPlugin;
+
+impl Plugin for UIBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<BindingsScreen>()
+            .init_resource::<Rebinding>()
+            .add_systems(
+                OnEnter(BindingsScreen::Open),
+                (create_ui, set_menus_visibility(Visibility::Hidden)),
+            )
+            .add_systems(
+                OnExit(BindingsScreen::Open),
+                (cleanup_ui, set_menus_visibility(Visibility::Inherited)),
+            )
+            .add_systems(
+                Update,
+                (
+                    capture_key,
+                    binding_button_interaction_system,
+                    reset_button_interaction_system,
+                    back_button_interaction_system,
+                    update_binding_texts.run_if(
+                        resource_changed::<ActionMap>.or_else(resource_changed::<Rebinding>),
+                    ),
+                )
+                    .chain()
+                    .run_if(in_state(BindingsScreen::Open)),
+            );
+    }
+}
+
+/// The key bindings screen, shown over the main menu or the pause menu
+#[derive(States, Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum BindingsScreen {
+    #[default]
+    Closed,
+    Open,
+}
+
+/// Action waiting for its new key, if any
+#[derive(Resource, Default)]
+struct Rebinding(Option<Action>);
+
+#[derive(Component)]
+struct BindingsUi;
+
+#[derive(Component)]
+struct BindingButton(Action);
+
+#[derive(Component)]
+struct BindingText(Action);
+
+#[derive(Component)]
+struct ConflictText;
+
+#[derive(Component)]
+struct ResetButton;
+
+#[derive(Component)]
+struct BackButton;
+
+/// List every action with its key, players on the left and the debug tools
+/// on the right. Clicking an action waits for its new key.
+fn create_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    action_map: Res<ActionMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    rebinding.0 = None;
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn((
+            BindingsUi,
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: Color::BLACK.with_alpha(0.8).into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "KEY BINDINGS",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 50.0,
+                    color: Color::WHITE,
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                "CLICK AN ACTION, THEN PRESS ITS NEW KEY (ESCAPE TO CANCEL)",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 18.0,
+                    color: Color::srgb(0.7, 0.7, 0.7),
+                },
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::vertical(Val::Px(10.0)),
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let (player_actions, other_actions): (Vec<Action>, Vec<Action>) =
+                        ActionMap::actions().partition(|action| {
+                            matches!(
+                                action,
+                                Action::TurnLeft(_) | Action::TurnRight(_) | Action::Boost(_)
+                            )
+                        });
+
+                    for actions in [player_actions, other_actions] {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    ..default()
+                                },
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                for action in actions {
+                                    spawn_binding_button(parent, action, &action_map, &font);
+                                }
+                            });
+                    }
+                });
+
+            parent.spawn((
+                ConflictText,
+                TextBundle::from_section(
+                    conflicts_label(&action_map),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 18.0,
+                        color: Color::from(tailwind::RED_500),
+                    },
+                ),
+            ));
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        margin: UiRect::top(Val::Px(10.0)),
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, ResetButton, (), "RESET".into(), 150.0, &font);
+                    spawn_button(
+                        parent,
+                        (BackButton, MenuBack),
+                        (),
+                        "BACK".into(),
+                        150.0,
+                        &font,
+                    );
+                });
+        });
+}
+
+fn spawn_binding_button(
+    parent: &mut ChildBuilder,
+    action: Action,
+    action_map: &ActionMap,
+    font: &Handle<Font>,
+) {
+    parent
+        .spawn((
+            BindingButton(action),
+            ButtonBundle {
+                style: Style {
+                    margin: UiRect::bottom(Val::Px(4.0)),
+                    width: Val::Px(320.0),
+                    height: Val::Px(30.0),
+                    border: UiRect::all(Val::Px(2.0)),
+                    padding: UiRect::horizontal(Val::Px(8.0)),
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(Color::BLACK),
+                background_color: NORMAL_BUTTON.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                BindingText(action),
+                TextBundle::from_section(
+                    binding_label(action, action_map, None),
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 20.0,
+                        color: binding_color(action, action_map, None),
+                    },
+                ),
+            ));
+        });
+}
+
+fn binding_label(action: Action, action_map: &ActionMap, rebinding: Option<Action>) -> String {
+    if rebinding == Some(action) {
+        format!("{}: ...", action.label())
+    } else {
+        format!("{}: {:?}", action.label(), action_map.key(action))
+    }
+}
+
+/// Highlight the action waiting for a key and the conflicting ones
+fn binding_color(action: Action, action_map: &ActionMap, rebinding: Option<Action>) -> Color {
+    if rebinding == Some(action) {
+        Color::from(tailwind::AMBER_400)
+    } else if action_map.is_conflicting(action) {
+        Color::from(tailwind::RED_500)
+    } else {
+        Color::srgb(0.9, 0.9, 0.9)
+    }
+}
+
+fn conflicts_label(action_map: &ActionMap) -> String {
+    action_map
+        .conflicts()
+        .iter()
+        .map(|(a, b)| format!("{} AND {} SHARE A KEY", a.label(), b.label()))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Bind the first key pressed to the action waiting for one
+fn capture_key(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut rebinding: ResMut<Rebinding>,
+    mut action_map: ResMut<ActionMap>,
+) {
+    let Some(action) = rebinding.0 else {
+        return;
+    };
+    let Some(key) = keyboard_input.get_just_pressed().next() else {
+        return;
+    };
+
+    if *key != KeyCode::Escape {
+        *action_map.key_mut(action) = *key;
+    }
+    rebinding.0 = None;
+}
+
+fn binding_button_interaction_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BorderColor, &BindingButton),
+        Changed<Interaction>,
+    >,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, mut border_color, button) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            rebinding.0 = Some(button.0);
+        }
+    }
+}
+
+fn reset_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<ResetButton>,
+    mut action_map: ResMut<ActionMap>,
+    mut rebinding: ResMut<Rebinding>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            *action_map = ActionMap::default();
+            rebinding.0 = None;
+        }
+    }
+}
+
+/// Save the bindings when leaving the screen
+fn back_button_interaction_system(
+    mut interaction_query: BtnInteractionQuery<BackButton>,
+    action_map: Res<ActionMap>,
+    mut next_state: ResMut<NextState<BindingsScreen>>,
+) {
+    for (interaction, _, mut border_color, _) in &mut interaction_query {
+        if update_button_border(interaction, &mut border_color) {
+            action_map.save();
+            next_state.set(BindingsScreen::Closed);
+        }
+    }
+}
+
+/// Hide the menu below the screen while it is open
+fn set_menus_visibility(visibility: Visibility) -> impl FnMut(Query<&mut Visibility, With<Menu>>) {
+    move |mut query| {
+        for mut menu_visibility in &mut query {
+            *menu_visibility = visibility;
+        }
+    }
+}
+
+fn update_binding_texts(
+    action_map: Res<ActionMap>,
+    rebinding: Res<Rebinding>,
+    mut binding_query: Query<(&mut Text, &BindingText), Without<ConflictText>>,
+    mut conflict_query: Query<&mut Text, With<ConflictText>>,
+) {
+    for (mut text, binding) in &mut binding_query {
+        text.sections[0].value = binding_label(binding.0, &action_map, rebinding.0);
+        text.sections[0].style.color = binding_color(binding.0, &action_map, rebinding.0);
+    }
+    for mut text in &mut conflict_query {
+        text.sections[0].value = conflicts_label(&action_map);
+    }
+}
+
+fn cleanup_ui(mut commands: Commands, query: Query<Entity, With<BindingsUi>>) {
+    for entity in &mut query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
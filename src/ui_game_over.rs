@@ -1,6 +1,9 @@
 use bevy::{app::AppExit, color::palettes::tailwind, prelude::*};
 
-use crate::app_state::AppState;
+use crate::{
+    app_state::AppState, components::player::PLAYER_PRESETS, score::PlayersScores,
+    settings::GameSettings,
+};
 
 pub struct UIGameOverPlugin;
 
@@ -30,8 +33,14 @@ struct ExitButton;
 #[derive(Component)]
 struct PlayButton;
 
-/// Create a simple UI with Play Button that set AppState to InGame
-fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
+/// Create a simple UI with the final scores and a Play Button that set
+/// AppState to InGame
+fn create_ui(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    scores: Option<Res<PlayersScores>>,
+    settings: Res<GameSettings>,
+) {
     commands
         .spawn((
             MainMenuUi,
@@ -58,6 +67,23 @@ fn create_ui(mut commands: Commands, asset_server: Res<AssetServer>) {
                 },
             ));
 
+            // Final score of each player, in the color of its snake
+            for (i, score) in scores.iter().flat_map(|scores| scores.0.iter()).enumerate() {
+                let name = if settings.is_bot(i) {
+                    "BOT"
+                } else {
+                    PLAYER_PRESETS[i].name
+                };
+                parent.spawn(TextBundle::from_section(
+                    format!("{} {} PTS", name, score.points),
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 24.0,
+                        color: Color::from(PLAYER_PRESETS[i].color),
+                    },
+                ));
+            }
+
             parent
                 .spawn((
                     PlayButton,